@@ -0,0 +1,5538 @@
+/// The kitties module: create, own and breed kitties.
+
+use support::{
+	decl_module, decl_storage, decl_event, ensure, StorageValue, StorageMap, StorageDoubleMap, Parameter,
+	traits::{Get, Currency, ReservableCurrency}, dispatch::Result,
+};
+use sr_primitives::traits::{
+	SimpleArithmetic, Bounded, Member, One, Zero, CheckedAdd, CheckedSub, Saturating, Convert, Verify,
+	UniqueSaturatedInto, Hash, Extrinsic as ExtrinsicT,
+};
+use sr_primitives::transaction_validity::{
+	TransactionValidity, TransactionLongevity, ValidTransaction, InvalidTransaction,
+};
+use codec::{Encode, Decode, Codec};
+use primitives::offchain::StorageKind;
+use runtime_io::blake2_128;
+use system::{ensure_signed, ensure_root, ensure_none};
+use rstd::{result, prelude::*};
+use client::decl_runtime_apis;
+
+/// Maximum number of kitties that can be queried in a single runtime API call.
+pub const MAX_QUERY_LENGTH: usize = 100;
+/// Maximum number of auctions that can end at the same block, bounding the expiry sweep.
+pub const MAX_AUCTIONS_PER_BLOCK: usize = 50;
+/// DNA bytes at or above this value are rare traits.
+pub const RARE_TRAIT_THRESHOLD: u8 = 0xf0;
+/// Maximum number of breeds that can be scheduled for the same block.
+pub const MAX_SCHEDULED_BREEDS_PER_BLOCK: usize = 10;
+/// Maximum number of kitties the DNA index migration scans in a single block.
+pub const MAX_DNA_MIGRATION_PER_BLOCK: u32 = 100;
+/// Maximum number of kitties kept in the rarity ranking.
+pub const MAX_RANKING_LENGTH: usize = 1000;
+/// Maximum number of sires an account can have on its breeding wishlist.
+pub const MAX_WISHLIST_LENGTH: usize = 20;
+/// Maximum number of accounts that can have the same sire on their wishlist.
+pub const MAX_WISHERS_PER_SIRE: usize = 50;
+/// Number of ancestor generations counted in the pedigree score.
+pub const MAX_PEDIGREE_DEPTH: u32 = 4;
+/// DNA byte whose top two bits encode the species, see `Species`.
+pub const SPECIES_DNA_BYTE: usize = 8;
+/// Randomness subject of the DNA of created kitties.
+const CREATE_SUBJECT: &[u8] = b"kitty-create";
+/// Randomness subject of breeding selectors.
+const BREED_SUBJECT: &[u8] = b"kitty-breed";
+/// Randomness subject of the DNA of hatched eggs.
+const EGG_SUBJECT: &[u8] = b"kitty-egg";
+/// Randomness subject of DNA mutations.
+const MUTATE_SUBJECT: &[u8] = b"kitty-mutate";
+/// Maximum length in bytes of a kitty name.
+pub const MAX_NAME_LENGTH: usize = 32;
+/// Maximum number of past names kept for a kitty.
+pub const MAX_NAME_HISTORY: usize = 10;
+/// `PausedOps` flag pausing the minting of new kitties.
+pub const PAUSE_MINT: u8 = 1 << 0;
+/// `PausedOps` flag pausing breeding.
+pub const PAUSE_BREED: u8 = 1 << 1;
+/// `PausedOps` flag pausing transfers between accounts.
+pub const PAUSE_TRANSFER: u8 = 1 << 2;
+/// `PausedOps` flag pausing listings, auctions and bids.
+pub const PAUSE_TRADE: u8 = 1 << 3;
+/// Offchain persistent storage key of the latest rarity ranking.
+pub const RARITY_RANKING_KEY: &[u8] = b"kitties::rarity_ranking";
+
+pub type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
+
+/// The module's configuration trait.
+pub trait Trait: system::Trait {
+	/// The overarching event type.
+	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+	/// The type used to index kitties.
+	type KittyIndex: Parameter + Member + SimpleArithmetic + Bounded + Default + Copy;
+	/// The currency kitties are traded in.
+	type Currency: ReservableCurrency<Self::AccountId>;
+	/// Number of blocks a parent has to wait before it can breed again.
+	type BreedCooldown: Get<Self::BlockNumber>;
+	/// Whether generation 0 parents are exempt from the breeding cooldown.
+	type Gen0NoCooldown: Get<bool>;
+	/// Whether DNA is derived from the kitty count and sender only, making kitties reproducible.
+	///
+	/// This is meant for demo and test networks, the DNA is fully predictable when enabled.
+	type DeterministicGenetics: Get<bool>;
+	/// Maximum difference between the generations of two parents, `u16::max_value()` disables it.
+	type MaxGenerationGap: Get<u16>;
+	/// Highest generation a bred kitty can have.
+	type MaxGeneration: Get<u16>;
+	/// Minimum free balance an account needs to create a kitty, keeping dust accounts from minting.
+	type MinCreatorBalance: Get<BalanceOf<Self>>;
+	/// Number of trailing DNA bytes a donor passes on in `breed_with_donor`, at most 15.
+	type DonorGeneCount: Get<u32>;
+	/// Whether the breeding selector is skewed towards the rarer parent, see `biased_selector`.
+	type RarityBiasedBreeding: Get<bool>;
+	/// Maximum number of kitties an account can mint or breed into, and receive with `safe_transfer`.
+	type MaxKittiesPerAccount: Get<Self::KittyIndex>;
+	/// Number of blocks between two rarity rankings published by the offchain worker.
+	type RankingInterval: Get<Self::BlockNumber>;
+	/// The runtime call the offchain worker submits the rarity ranking as.
+	type Call: From<Call<Self>>;
+	/// The extrinsic the offchain worker wraps the unsigned rarity ranking in.
+	type UncheckedExtrinsic: ExtrinsicT<Call = <Self as Trait>::Call> + Encode;
+	/// Account the kitties module pays rewards from.
+	type Treasury: Get<Self::AccountId>;
+	/// Amount paid by the treasury to the owner of a burned kitty.
+	type BurnReward: Get<BalanceOf<Self>>;
+	/// Number of kitties minted by `claim_starter_pack`.
+	type StarterPackSize: Get<u32>;
+	/// Fee paid to the treasury by the sender of `transfer_kitty`, zero disables it.
+	type TransferFee: Get<BalanceOf<Self>>;
+	/// Number of breeding tokens granted to the breeder of a kitty.
+	type BreedingTokenReward: Get<u32>;
+	/// Fee paid to the treasury for breeding, before scaling by `BreedingFeeCurve`.
+	type BaseBreedingFee: Get<BalanceOf<Self>>;
+	/// Maps the highest parent generation to the multiplier of `BaseBreedingFee`.
+	type BreedingFeeCurve: Convert<u16, u32>;
+	/// Whether breeding two generation 0 parents is free of the breeding fee.
+	type FreeGen0Breed: Get<bool>;
+	/// Levels a kitty gains for each kitty sacrificed to it.
+	type LevelsPerFodder: Get<u32>;
+	/// Maximum number of kitties a pair of parents can breed, in either order.
+	type MaxPairOffspring: Get<u32>;
+	/// A bid placed within this many blocks of the auction end extends the auction by as many blocks.
+	type AntiSnipeWindow: Get<Self::BlockNumber>;
+	/// Maximum number of kitties an account can transfer out within `TransferWindow` blocks.
+	type MaxTransfersPerWindow: Get<u32>;
+	/// Length in blocks of the transfer rate limiting window.
+	type TransferWindow: Get<Self::BlockNumber>;
+	/// Maximum number of items in a batch call.
+	type MaxBatchSize: Get<u32>;
+	/// Whether a transferred kitty can breed right away for its new owner.
+	///
+	/// When disabled the breeding cooldown carries over to the new owner, so trading kitties
+	/// back and forth doesn't speed up breeding.
+	type ResetCooldownOnTransfer: Get<bool>;
+	/// Amount a bid has to exceed the highest bid of an auction by.
+	type MinBidIncrement: Get<BalanceOf<Self>>;
+	/// Whether two kitties can't have the same name.
+	type UniqueNames: Get<bool>;
+	/// The signature of minting vouchers.
+	type Signature: Parameter + Verify<Signer = Self::AccountId>;
+	/// Account signing the vouchers redeemed with `redeem_voucher`.
+	type VoucherSigner: Get<Self::AccountId>;
+	/// Whether offspring genders are nudged towards an even male/female ratio.
+	type BalancedGenders: Get<bool>;
+	/// Whether `create` mints kitties into the incubator, leaving them unowned until hatched.
+	type Incubation: Get<bool>;
+	/// Fee paid to the treasury to hatch an incubating kitty.
+	type HatchFee: Get<BalanceOf<Self>>;
+	/// DNA bits always inherited from the matron, the first parent, when breeding.
+	///
+	/// Locking the lowest bit of the first byte also passes on the matron's gender.
+	type LockedTraitMask: Get<[u8; 16]>;
+	/// Highest price a kitty can be listed or auctioned at, `Bounded::max_value()` for no cap.
+	type MaxListingPrice: Get<BalanceOf<Self>>;
+	/// Deposit reserved from the creator of a kitty until it is burned.
+	type CreationDeposit: Get<BalanceOf<Self>>;
+	/// Whether the creation deposit moves to the new owner of a transferred kitty.
+	///
+	/// When enabled the new owner has the deposit reserved and the previous holder gets theirs
+	/// back, otherwise it stays reserved from the creator.
+	type DepositFollowsKitty: Get<bool>;
+	/// Fee paid to the treasury for a cooldown speedup when the owner has no breeding token.
+	type SpeedupFee: Get<BalanceOf<Self>>;
+	/// Block until which only allowlisted accounts can create kitties.
+	type PresaleEnd: Get<Self::BlockNumber>;
+	/// Whether only kitties of the same species can breed together.
+	type SameSpeciesOnly: Get<bool>;
+	/// Whether batch calls emit a single summary event rather than one event per kitty.
+	type CompactBatchEvents: Get<bool>;
+	/// Whether burned kitties are kept in the graveyard, without an owner, rather than deleted.
+	///
+	/// This keeps the DNA and lineage of burned ancestors queryable.
+	type UseTombstones: Get<bool>;
+	/// Highest license tier a kitty can have.
+	type MaxLicenseTier: Get<u8>;
+	/// Fee paid to the treasury for each license tier a kitty is upgraded by.
+	type LicenseUpgradeFee: Get<BalanceOf<Self>>;
+	/// Maximum number of operators an owner can approve for all their kitties.
+	type MaxOperatorsPerOwner: Get<u32>;
+	/// Deposit reserved while an egg is committed.
+	type EggDeposit: Get<BalanceOf<Self>>;
+	/// Blocks after committing an egg before it can be revealed.
+	type EggRevealDelay: Get<Self::BlockNumber>;
+	/// Blocks an egg can be revealed for once the delay has passed.
+	type EggRevealWindow: Get<Self::BlockNumber>;
+}
+
+/// A kitty is identified by its 128 bit DNA.
+#[cfg_attr(feature = "std", derive(Debug))]
+#[derive(Encode, Decode, Clone, PartialEq, Eq)]
+pub struct Kitty(pub [u8; 16]);
+
+#[cfg_attr(feature = "std", derive(Debug))]
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq)]
+pub enum Gender {
+	Male = 0,
+	Female = 1,
+}
+
+/// The species of a kitty, from the top two bits of DNA byte `SPECIES_DNA_BYTE`.
+#[cfg_attr(feature = "std", derive(Debug))]
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq)]
+pub enum Species {
+	Tabby = 0,
+	Siamese = 1,
+	Persian = 2,
+	Sphynx = 3,
+}
+
+/// An English auction of a kitty.
+#[cfg_attr(feature = "std", derive(Debug))]
+#[derive(Encode, Decode, Clone, PartialEq, Eq)]
+pub struct Auction<AccountId, Balance, BlockNumber> {
+	/// The owner selling the kitty.
+	pub seller: AccountId,
+	/// The lowest acceptable bid.
+	pub start_price: Balance,
+	/// The block from which no more bids are accepted, pushed back by late bids.
+	pub end_block: BlockNumber,
+	/// The highest bid so far, its amount is reserved from the bidder.
+	pub highest_bid: Option<(AccountId, Balance)>,
+}
+
+/// An entry of the doubly linked list of the kitties owned by an account.
+///
+/// The entry keyed by `None` is the head of the list, its `next` is the first kitty and its
+/// `prev` the last one.
+#[cfg_attr(feature = "std", derive(Debug))]
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, Default)]
+pub struct KittyLinkedItem<KittyIndex> {
+	/// The previous kitty of the owner, `None` for the head.
+	pub prev: Option<KittyIndex>,
+	/// The next kitty of the owner, `None` for the head.
+	pub next: Option<KittyIndex>,
+}
+
+/// A summary of the kitties owned and listed by an account.
+#[cfg_attr(feature = "std", derive(Debug))]
+#[derive(Encode, Decode, Clone, PartialEq, Eq)]
+pub struct AccountSummary<KittyIndex, Balance> {
+	/// The kitties owned by the account.
+	pub owned: Vec<KittyIndex>,
+	/// The number of kitties owned by the account.
+	pub total_owned: u32,
+	/// The number of kitties created or bred by the account.
+	pub created_count: u32,
+	/// The owned kitties that are listed for sale, with their prices.
+	pub listed: Vec<(KittyIndex, Balance)>,
+	/// The sum of the prices of all listed kitties.
+	pub total_listed_value: Balance,
+}
+
+/// A kitty with the state a UI displays alongside it.
+#[cfg_attr(feature = "std", derive(Debug))]
+#[derive(Encode, Decode, Clone, PartialEq, Eq)]
+pub struct KittyProfile<AccountId, Balance> {
+	/// The kitty itself.
+	pub kitty: Kitty,
+	/// The owner of the kitty, `None` while it is incubating.
+	pub owner: Option<AccountId>,
+	/// The generation of the kitty.
+	pub generation: u16,
+	/// The name of the kitty, empty if it has none.
+	pub name: Vec<u8>,
+	/// The sale price of the kitty, if it is listed.
+	pub price: Option<Balance>,
+	/// Blocks until the kitty can breed again, 0 if it is ready.
+	pub cooldown_remaining: u32,
+	/// The commercial license tier of the kitty.
+	pub license: u8,
+}
+
+/// The facts about a kitty that never change, for provenance displays.
+#[cfg_attr(feature = "std", derive(Debug))]
+#[derive(Encode, Decode, Clone, PartialEq, Eq)]
+pub struct BirthCertificate<AccountId, KittyIndex, BlockNumber> {
+	/// The DNA of the kitty as lowercase hex.
+	pub dna_hex: Vec<u8>,
+	/// The generation of the kitty.
+	pub generation: u16,
+	/// The parents of the kitty, `None` if it wasn't bred.
+	pub parents: Option<(KittyIndex, KittyIndex)>,
+	/// The block the kitty was born at.
+	pub birth_block: BlockNumber,
+	/// The account that created or bred the kitty, `None` if it predates creator tracking.
+	pub creator: Option<AccountId>,
+}
+
+/// How kitty ids are laid out, for tooling displaying them.
+#[cfg_attr(feature = "std", derive(Debug))]
+#[derive(Encode, Decode, Clone, PartialEq, Eq)]
+pub struct KittyIndexInfo<KittyIndex> {
+	/// The id of the first kitty, later kitties are numbered up from it.
+	pub first_id: KittyIndex,
+	/// The bit width of the kitty index type.
+	pub bits: u32,
+}
+
+/// Why two kitties can't be bred together.
+#[cfg_attr(feature = "std", derive(Debug))]
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq)]
+pub enum Incompatibility {
+	/// One of the kitties doesn't exist or has no owner.
+	InvalidKitty,
+	/// Both ids are the same kitty.
+	SameKitty,
+	/// The kitties have different owners.
+	DifferentOwners,
+	/// One of the kitties is retired.
+	Retired,
+	/// The generations of the kitties are too far apart.
+	GenerationGap,
+	/// The offspring would exceed the maximum generation.
+	MaxGeneration,
+	/// One of the kitties is on breeding cooldown.
+	OnCooldown,
+	/// The kitties are of different species, see `SameSpeciesOnly`.
+	SpeciesMismatch,
+	/// The pair already bred `MaxPairOffspring` kitties.
+	PairLimit,
+}
+
+impl From<Incompatibility> for &'static str {
+	fn from(incompatibility: Incompatibility) -> &'static str {
+		match incompatibility {
+			Incompatibility::InvalidKitty => "Invalid kitty_id",
+			Incompatibility::SameKitty => "Needs different parent",
+			Incompatibility::DifferentOwners => "Not owner of kitty",
+			Incompatibility::Retired => "Kitty is retired",
+			Incompatibility::GenerationGap => "Generation gap too large",
+			Incompatibility::MaxGeneration => "Max generation reached",
+			Incompatibility::OnCooldown => "Kitty is on breeding cooldown",
+			Incompatibility::SpeciesMismatch => "Species mismatch",
+			Incompatibility::PairLimit => "Pair offspring limit reached",
+		}
+	}
+}
+
+/// Whether two kitties can be bred together, for UIs to display in one call.
+#[cfg_attr(feature = "std", derive(Debug))]
+#[derive(Encode, Decode, Clone, PartialEq, Eq)]
+pub struct CompatibilityReport<BlockNumber> {
+	/// Whether the owner of the kitties can breed them right now.
+	pub can_breed: bool,
+	/// Why the kitties can't be bred, `None` if they can.
+	pub reason: Option<Incompatibility>,
+	/// Blocks until both kitties are off cooldown, `None` if neither is on cooldown.
+	pub cooldown_remaining: Option<BlockNumber>,
+	/// The DNA distance between the kitties, see `dna_distance`.
+	pub dna_distance: u32,
+}
+
+impl Kitty {
+	/// The lowest bit of the first DNA byte encodes the gender.
+	pub fn gender(&self) -> Gender {
+		gender_from_byte(self.0[0])
+	}
+
+	/// The species of the kitty.
+	pub fn species(&self) -> Species {
+		match self.0[SPECIES_DNA_BYTE] >> 6 {
+			0 => Species::Tabby,
+			1 => Species::Siamese,
+			2 => Species::Persian,
+			_ => Species::Sphynx,
+		}
+	}
+
+	/// The number of rare traits, i.e. DNA bytes of at least `RARE_TRAIT_THRESHOLD`.
+	pub fn rarity(&self) -> u32 {
+		self.0.iter().filter(|&&byte| byte >= RARE_TRAIT_THRESHOLD).count() as u32
+	}
+
+	/// The traits front-ends render a kitty from, always in this order:
+	///
+	/// 0. gender, `0` for male and `1` for female
+	/// 1. body, DNA byte 1
+	/// 2. eyes, DNA byte 2
+	/// 3. pattern, DNA byte 3
+	/// 4. - 6. color as red, green and blue, DNA bytes 4 to 6
+	/// 7. accessory, DNA byte 7
+	///
+	/// The remaining DNA bytes don't affect rendering. New traits are only ever appended.
+	pub fn render_traits(&self) -> Vec<u8> {
+		let mut traits = Vec::with_capacity(8);
+		traits.push(self.gender() as u8);
+		traits.extend_from_slice(&self.0[1..8]);
+		traits
+	}
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as Kitties {
+		/// Stores all the kitties, key is the kitty id / index
+		pub Kitties get(kitties): map T::KittyIndex => Option<Kitty>;
+		/// The last rarity ranking submitted by an offchain worker, see `Module::rarity_ranking`
+		pub RarityRanking get(published_rarity_ranking): Vec<T::KittyIndex>;
+		/// The block the last submitted rarity ranking was computed at
+		pub RankingComputedAt get(ranking_computed_at): T::BlockNumber;
+		/// Stores the total number of kitties. i.e. the next kitty index
+		pub KittiesCount get(kitties_count): T::KittyIndex;
+		/// Incremented on every random value, so no two draws share an entropy payload
+		Nonce get(nonce): u64;
+		/// The operations paused by governance, as `PAUSE_*` flags
+		pub PausedOps get(paused_ops): u8;
+		/// Get the base breeding fee set by root, replacing `BaseBreedingFee` when set
+		pub BreedFee get(breed_fee): Option<BalanceOf<T>>;
+		/// Get the number of kitties an account can still create during the presale
+		pub Allowlist get(allowance): map T::AccountId => u32;
+
+		/// Get the entry of a kitty in the owned kitties list of an account, or the list head with `None`
+		pub OwnedKitties get(owned_kitties): map (T::AccountId, Option<T::KittyIndex>) => Option<KittyLinkedItem<T::KittyIndex>>;
+		/// Get number of kitties by account ID
+		pub OwnedKittiesCount get(owned_kitties_count): map T::AccountId => T::KittyIndex;
+		/// Get kitty owner by kitty ID
+		pub KittyOwners get(kitty_owner): map T::KittyIndex => Option<T::AccountId>;
+		/// Get number of kitties created or bred by account ID
+		pub CreatedKittiesCount get(created_count): map T::AccountId => u32;
+		/// Get the account that created or bred a kitty
+		pub KittyCreators get(creator_of): map T::KittyIndex => Option<T::AccountId>;
+		/// Whether an account is frozen and can't receive kitties through `safe_transfer`
+		pub FrozenAccounts get(is_frozen): map T::AccountId => bool;
+		/// Whether an account already claimed its starter pack
+		pub StarterPackClaimed get(starter_pack_claimed): map T::AccountId => bool;
+		/// Sires an account would like to breed with
+		pub BreedWishlist get(wishlist): map T::AccountId => Vec<T::KittyIndex>;
+		/// Accounts having a sire on their wishlist
+		pub SireWishers get(sire_wishers): map T::KittyIndex => Vec<T::AccountId>;
+		/// Whether a kitty is offered as a sire by its owner
+		pub SireOffers get(is_sire_offered): map T::KittyIndex => bool;
+		/// Breeding tokens earned by an account, spent to mutate a kitty
+		pub BreedingTokens get(tokens): map T::AccountId => u32;
+		/// Start of the current transfer window of an account and the kitties it transferred out since
+		pub TransferCount get(transfer_count): map T::AccountId => (T::BlockNumber, u32);
+
+		/// Get kitty generation by kitty ID, kitties created from scratch are generation 0
+		pub KittyGeneration get(generation_of): map T::KittyIndex => u16;
+		/// Get the living kitties of a generation, kept in step by `Module::set_generation`
+		pub KittiesByGeneration get(kitties_by_generation): map u16 => Vec<T::KittyIndex>;
+		/// Get the parents of a bred kitty by kitty ID
+		pub KittyParents get(parents_of): map T::KittyIndex => Option<(T::KittyIndex, T::KittyIndex)>;
+		/// Get the kitties with the given kitty as a recorded parent, by increasing id
+		pub KittyChildren get(children_of): map T::KittyIndex => Vec<T::KittyIndex>;
+		/// Get the block from which a kitty can breed again by kitty ID
+		pub KittyCooldownEnd get(cooldown_end_of): map T::KittyIndex => T::BlockNumber;
+		/// Get the block a kitty was born in by kitty ID
+		pub KittyBirthBlock get(birth_block_of): map T::KittyIndex => T::BlockNumber;
+		/// Get the number of male and female kitties
+		pub GenderCounts get(gender_counts): (u64, u64);
+
+		/// Get the account approved to transfer a kitty on behalf of its owner by kitty ID
+		pub KittyApprovals get(approved_of): map T::KittyIndex => Option<T::AccountId>;
+		/// Whether an operator may transfer all kitties of an owner, keyed by owner and operator
+		pub OperatorApprovals get(is_approved_for_all): double_map T::AccountId, blake2_256(T::AccountId) => bool;
+		/// Get the number of operators approved by an owner
+		pub OperatorCount get(operator_count): map T::AccountId => u32;
+		/// Whether a voucher for an account and DNA has been redeemed
+		pub RedeemedVouchers get(is_voucher_redeemed): map (T::AccountId, [u8; 16]) => bool;
+		/// Get the commitment of the egg of an account, the block it was committed at and its deposit
+		pub Eggs get(egg_of): map T::AccountId => Option<(T::Hash, T::BlockNumber, BalanceOf<T>)>;
+		/// Get the sum of the deposits reserved for committed eggs
+		pub TotalEggDeposits get(total_egg_deposits): BalanceOf<T>;
+		/// Whether a consignee accepts kitties listed on its behalf by a consignor
+		pub ConsignmentApprovals get(accepts_consignment): double_map T::AccountId, blake2_256(T::AccountId) => bool;
+		/// Get the number of kitties bred from a pair of parents, keyed by the lower id first
+		pub PairOffspringCount get(pair_offspring_count): double_map T::KittyIndex, blake2_256(T::KittyIndex) => u32;
+
+		/// Get the name of a kitty by kitty ID, empty if it has none
+		pub KittyNames get(name_of): map T::KittyIndex => Vec<u8>;
+		/// Get the commercial license tier of a kitty, 0 for new kitties
+		pub KittyLicense get(license_of): map T::KittyIndex => u8;
+		/// Get the level of a kitty, raised by sacrificing other kitties to it
+		pub KittyLevel get(level_of): map T::KittyIndex => u32;
+		/// Get the names given to a kitty and the blocks they were set at, oldest first
+		pub KittyNameHistory get(name_history_of): map T::KittyIndex => Vec<(Vec<u8>, T::BlockNumber)>;
+		/// Get the kitty holding a name, only maintained with `UniqueNames`
+		pub NameOwner get(name_owner): map Vec<u8> => Option<T::KittyIndex>;
+
+		/// Whether a kitty has been retired from breeding by kitty ID
+		pub RetiredKitties get(is_retired): map T::KittyIndex => bool;
+
+		/// Get the asking price of a kitty listed for sale by kitty ID
+		pub KittyPrices get(kitty_price): map T::KittyIndex => Option<BalanceOf<T>>;
+		/// Whether a kitty is locked against transfer, e.g. while it is on auction
+		pub KittyLocks get(is_locked): map T::KittyIndex => bool;
+		/// Get the block until which the owner locked a kitty against transfer, see `lock_until`
+		pub KittyTimeLocks get(time_locked_until): map T::KittyIndex => T::BlockNumber;
+		/// Get the kitty holding a DNA, so that every DNA is unique
+		pub DnaExists get(kitties_id_by_dna): map [u8; 16] => Option<T::KittyIndex>;
+		/// Get the account and amount of the creation deposit reserved for a kitty
+		pub KittyDeposits get(deposit_of): map T::KittyIndex => Option<(T::AccountId, BalanceOf<T>)>;
+		/// Whether a kitty was burned and only kept for its DNA and lineage, see `UseTombstones`
+		pub Graveyard get(is_tombstoned): map T::KittyIndex => bool;
+		/// Whether a kitty is in the incubator, waiting to be hatched by its owner
+		pub Incubating get(is_incubating): map T::KittyIndex => bool;
+		/// Whether `DnaExists` has been rebuilt from the existing kitties
+		pub DnaIndexMigrated get(dna_index_migrated): bool;
+		/// The next kitty to be scanned by the DNA index migration
+		pub DnaIndexCursor get(dna_index_cursor): T::KittyIndex;
+		/// Kitties sharing their DNA with an older kitty, found by the DNA index migration
+		pub DuplicateDnaKitties get(duplicate_dna_kitties): Vec<T::KittyIndex>;
+
+		/// Get the running auction of a kitty by kitty ID
+		pub Auctions get(auction_of): map T::KittyIndex => Option<Auction<T::AccountId, BalanceOf<T>, T::BlockNumber>>;
+		/// Get the kitties whose auction ends at a block by block number
+		pub AuctionsEndingAt get(auctions_ending_at): map T::BlockNumber => Vec<T::KittyIndex>;
+		/// Get the breeds scheduled for a block, by breeder and parents
+		pub ScheduledBreeds get(scheduled_breeds): map T::BlockNumber => Vec<(T::AccountId, T::KittyIndex, T::KittyIndex)>;
+	}
+	add_extra_genesis {
+		/// Kitties to seed at genesis, as owner, DNA and generation
+		config(kitties): Vec<(T::AccountId, [u8; 16], u16)>;
+		build(|config: &GenesisConfig<T>| {
+			for (owner, dna, generation) in config.kitties.iter() {
+				assert!(*generation <= T::MaxGeneration::get(), "Genesis kitty generation above MaxGeneration");
+				assert!(!<DnaExists<T>>::exists(dna), "Duplicate genesis kitty DNA");
+				let kitty_id = <Module<T>>::next_kitty_id().expect("Too many genesis kitties");
+				<Module<T>>::insert_kitty(owner, kitty_id, Kitty(*dna), *generation).expect("Too many genesis kitties");
+			}
+			// `insert_kitty` keeps the DNA index of a new chain up to date
+			<DnaIndexMigrated>::put(true);
+		});
+	}
+}
+
+decl_module! {
+	/// The module declaration.
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		fn deposit_event() = default;
+
+		fn on_initialize(now: T::BlockNumber) {
+			if !Self::dna_index_migrated() {
+				Self::migrate_dna_index();
+			}
+			Self::sweep_expired_auctions(now);
+			Self::run_scheduled_breeds(now);
+		}
+
+		fn offchain_worker(now: T::BlockNumber) {
+			let interval = T::RankingInterval::get();
+			if !interval.is_zero() && (now % interval).is_zero() {
+				Self::publish_rarity_ranking(now);
+			}
+		}
+
+		/// Store the rarity ranking an offchain worker computed at block `at`, see `ValidateUnsigned`
+		pub fn submit_rarity_ranking(origin, ranking: Vec<T::KittyIndex>, at: T::BlockNumber) {
+			ensure_none(origin)?;
+			Self::ensure_valid_ranking(&ranking, at)?;
+
+			<RarityRanking<T>>::put(ranking);
+			<RankingComputedAt<T>>::put(at);
+		}
+
+		/// Create a new kitty
+		pub fn create(origin) {
+			let sender = ensure_signed(origin)?;
+			let allowance = Self::ensure_can_create(&sender)?;
+
+			if T::Incubation::get() {
+				Self::incubate_kitty(&sender)?;
+			} else {
+				Self::mint_kitty(&sender, true)?;
+			}
+			if let Some(allowance) = allowance {
+				<Allowlist<T>>::insert(&sender, allowance);
+			}
+		}
+
+		/// Take an incubating kitty out of the incubator, paying `HatchFee` to own it
+		pub fn hatch(origin, kitty_id: T::KittyIndex) {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_not_paused(PAUSE_MINT)?;
+
+			ensure!(Self::is_incubating(kitty_id), "Kitty is not incubating");
+			ensure!(Self::owned_kitties_count(&sender) < T::MaxKittiesPerAccount::get(), "Too many kitties owned");
+
+			let fee = T::HatchFee::get();
+			if !fee.is_zero() {
+				T::Currency::transfer(&sender, &T::Treasury::get(), fee).map_err(|_| "Cannot pay hatch fee")?;
+			}
+
+			<Incubating<T>>::remove(kitty_id);
+			Self::insert_owned_kitty(&sender, kitty_id)?;
+
+			Self::deposit_event(RawEvent::Hatched(sender, kitty_id));
+		}
+
+		/// Create `count` kitties at once
+		pub fn batch_create(origin, count: u32) {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_not_paused(PAUSE_MINT)?;
+
+			Self::ensure_batch_size(count as usize)?;
+			ensure!(T::Currency::free_balance(&sender) >= T::MinCreatorBalance::get(), "Balance too low to create kitty");
+			let batch_size: T::KittyIndex = count.into();
+			ensure!(
+				Self::owned_kitties_count(&sender).checked_add(&batch_size)
+					.map_or(false, |owned| owned <= T::MaxKittiesPerAccount::get()),
+				"Too many kitties owned"
+			);
+			ensure!(Self::kitties_count().checked_add(&batch_size).is_some(), "Kitties count overflow");
+			Self::ensure_can_reserve_deposits(&sender, count)?;
+			let allowance = Self::presale_allowance(&sender, count)?;
+
+			if let Some(allowance) = allowance {
+				<Allowlist<T>>::insert(&sender, allowance);
+			}
+			let compact = T::CompactBatchEvents::get();
+			let mut kitty_ids = Vec::new();
+			for _ in 0..count {
+				kitty_ids.push(Self::mint_kitty(&sender, !compact)?);
+			}
+			if compact {
+				Self::deposit_event(RawEvent::BatchCreated(sender, kitty_ids));
+			}
+		}
+
+		/// Mint the kitty of a voucher, signed by `VoucherSigner` over the sender and DNA
+		pub fn redeem_voucher(origin, dna: [u8; 16], signature: T::Signature) {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_not_paused(PAUSE_MINT)?;
+
+			let voucher = (sender.clone(), dna);
+			ensure!(!Self::is_voucher_redeemed(&voucher), "Voucher already redeemed");
+			ensure!(
+				voucher.using_encoded(|payload| signature.verify(payload, &T::VoucherSigner::get())),
+				"Invalid voucher signature"
+			);
+			ensure!(!<DnaExists<T>>::exists(&dna), "Kitty DNA already exists");
+			ensure!(Self::owned_kitties_count(&sender) < T::MaxKittiesPerAccount::get(), "Too many kitties owned");
+			let kitty_id = Self::next_kitty_id()?;
+
+			<RedeemedVouchers<T>>::insert(&voucher, true);
+			Self::insert_kitty(&sender, kitty_id, Kitty(dna), 0)?;
+			Self::deposit_event(RawEvent::CreatedWithDna(sender, kitty_id, dna));
+		}
+
+		/// Commit to an egg with the hash of the sender and a secret, reserving `EggDeposit`
+		pub fn commit_egg(origin, commitment: T::Hash) {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_not_paused(PAUSE_MINT)?;
+
+			let now = <system::Module<T>>::block_number();
+			// An expired egg is replaced, keeping its deposit
+			let deposit = match Self::egg_of(&sender) {
+				Some((_, committed, deposit)) => {
+					ensure!(now > Self::egg_expiry(committed), "Egg already committed");
+					deposit
+				},
+				None => {
+					let deposit = T::EggDeposit::get();
+					T::Currency::reserve(&sender, deposit).map_err(|_| "Cannot reserve egg deposit")?;
+					<TotalEggDeposits<T>>::mutate(|total| *total = total.saturating_add(deposit));
+					deposit
+				},
+			};
+
+			<Eggs<T>>::insert(&sender, (commitment, now, deposit));
+		}
+
+		/// Reveal the secret of the sender's egg, minting a kitty and returning the deposit
+		pub fn reveal_egg(origin, secret: Vec<u8>) {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_not_paused(PAUSE_MINT)?;
+
+			let (commitment, committed, _) = Self::egg_of(&sender).ok_or("No egg committed")?;
+			let now = <system::Module<T>>::block_number();
+			ensure!(now >= committed + T::EggRevealDelay::get(), "Egg not ready");
+			ensure!(now <= Self::egg_expiry(committed), "Egg expired");
+			ensure!(T::Hashing::hash_of(&(&sender, &secret)) == commitment, "Egg commitment mismatch");
+			ensure!(Self::owned_kitties_count(&sender) < T::MaxKittiesPerAccount::get(), "Too many kitties owned");
+			let kitty_id = Self::next_kitty_id()?;
+
+			// The secret was fixed before the randomness of this block was known
+			let dna = (secret, Self::random_value(EGG_SUBJECT, &sender)).using_encoded(blake2_128);
+			ensure!(!<DnaExists<T>>::exists(&dna), "Kitty DNA already exists");
+
+			Self::remove_egg(&sender);
+			Self::insert_kitty(&sender, kitty_id, Kitty(dna), 0)?;
+			Self::deposit_event(RawEvent::CreatedWithDna(sender, kitty_id, dna));
+		}
+
+		/// Give up the sender's expired egg, returning its deposit
+		pub fn reclaim_egg(origin) {
+			let sender = ensure_signed(origin)?;
+
+			let (_, committed, _) = Self::egg_of(&sender).ok_or("No egg committed")?;
+			ensure!(<system::Module<T>>::block_number() > Self::egg_expiry(committed), "Egg not expired");
+
+			Self::remove_egg(&sender);
+		}
+
+		/// Mint `StarterPackSize` kitties to the sender, once per account
+		pub fn claim_starter_pack(origin) {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_not_paused(PAUSE_MINT)?;
+
+			ensure!(!Self::starter_pack_claimed(&sender), "Starter pack already claimed");
+			let size = T::StarterPackSize::get();
+			let pack_size: T::KittyIndex = size.into();
+			ensure!(
+				Self::owned_kitties_count(&sender).checked_add(&pack_size)
+					.map_or(false, |count| count <= T::MaxKittiesPerAccount::get()),
+				"Too many kitties owned"
+			);
+			ensure!(Self::kitties_count().checked_add(&pack_size).is_some(), "Kitties count overflow");
+			Self::ensure_can_reserve_deposits(&sender, size)?;
+
+			<StarterPackClaimed<T>>::insert(&sender, true);
+			for _ in 0..size {
+				Self::mint_kitty(&sender, true)?;
+			}
+		}
+
+		/// Breed kitties
+		pub fn breed(origin, kitty_id_1: T::KittyIndex, kitty_id_2: T::KittyIndex) {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_not_paused(PAUSE_BREED)?;
+
+			Self::do_breed(&sender, kitty_id_1, kitty_id_2, None)?;
+		}
+
+		/// Breed kitties, splicing the trailing DNA bytes of a donor kitty into the offspring
+		pub fn breed_with_donor(origin, matron: T::KittyIndex, sire: T::KittyIndex, donor: T::KittyIndex) {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_not_paused(PAUSE_BREED)?;
+
+			Self::do_breed(&sender, matron, sire, Some(donor))?;
+		}
+
+		/// Breed several pairs of kitties, either all of them or none
+		pub fn batch_breed(origin, pairs: Vec<(T::KittyIndex, T::KittyIndex)>) {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_not_paused(PAUSE_BREED)?;
+
+			Self::ensure_batch_size(pairs.len())?;
+			// A failed dispatch keeps its storage changes, so check the whole batch first
+			let mut parents = Vec::new();
+			let mut total_fee: BalanceOf<T> = Zero::zero();
+			for (i, &(kitty_id_1, kitty_id_2)) in pairs.iter().enumerate() {
+				let (_, _, generation) = Self::ensure_can_breed(&sender, kitty_id_1, kitty_id_2)?;
+				let pair = pair_key(kitty_id_1, kitty_id_2);
+				let repeats = pairs[..i].iter().filter(|&&(id_1, id_2)| pair_key(id_1, id_2) == pair).count() as u32;
+				ensure!(
+					Self::pair_offspring_count(&pair.0, &pair.1).saturating_add(repeats) < T::MaxPairOffspring::get(),
+					"Pair offspring limit reached"
+				);
+				for kitty_id in [kitty_id_1, kitty_id_2].iter() {
+					ensure!(
+						!Self::has_cooldown(*kitty_id) || !parents.contains(kitty_id),
+						"Kitty would be on breeding cooldown"
+					);
+					parents.push(*kitty_id);
+				}
+				total_fee = total_fee.saturating_add(Self::breeding_fee(generation - 1));
+			}
+			let batch_size: T::KittyIndex = (pairs.len() as u32).into();
+			ensure!(
+				Self::owned_kitties_count(&sender).checked_add(&batch_size)
+					.map_or(false, |owned| owned <= T::MaxKittiesPerAccount::get()),
+				"Too many kitties owned"
+			);
+			ensure!(Self::kitties_count().checked_add(&batch_size).is_some(), "Kitties count overflow");
+			ensure!(T::Currency::free_balance(&sender) >= total_fee, "Cannot pay breeding fee");
+
+			for (kitty_id_1, kitty_id_2) in pairs {
+				Self::do_breed(&sender, kitty_id_1, kitty_id_2, None)?;
+			}
+		}
+
+		/// Breed two kitties at a future block, they are checked again when the block comes
+		pub fn schedule_breed(origin, kitty_id_1: T::KittyIndex, kitty_id_2: T::KittyIndex, at_block: T::BlockNumber) {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(at_block > <system::Module<T>>::block_number(), "Scheduled block must be in the future");
+			ensure!(kitty_id_1 != kitty_id_2, "Needs different parent");
+			ensure!(Self::kitty_owner(kitty_id_1).as_ref() == Some(&sender), "Not owner of kitty_id_1");
+			ensure!(Self::kitty_owner(kitty_id_2).as_ref() == Some(&sender), "Not owner of kitty_id_2");
+			let mut scheduled = Self::scheduled_breeds(at_block);
+			ensure!(scheduled.len() < MAX_SCHEDULED_BREEDS_PER_BLOCK, "Too many breeds scheduled at this block");
+
+			scheduled.push((sender, kitty_id_1, kitty_id_2));
+			<ScheduledBreeds<T>>::insert(at_block, scheduled);
+		}
+
+		/// Cancel a breed scheduled by the sender
+		pub fn cancel_scheduled_breed(origin, kitty_id_1: T::KittyIndex, kitty_id_2: T::KittyIndex, at_block: T::BlockNumber) {
+			let sender = ensure_signed(origin)?;
+
+			let mut scheduled = Self::scheduled_breeds(at_block);
+			let breed = (sender, kitty_id_1, kitty_id_2);
+			let position = scheduled.iter().position(|item| *item == breed).ok_or("Breed is not scheduled")?;
+
+			scheduled.remove(position);
+			<ScheduledBreeds<T>>::insert(at_block, scheduled);
+		}
+
+		/// Transfer a kitty to new owner
+		pub fn transfer_kitty(origin, to: T::AccountId, kitty_id: T::KittyIndex) {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_not_paused(PAUSE_TRANSFER)?;
+
+			// Check the transfer before charging, a failed dispatch doesn't revert the payment
+			Self::ensure_can_transfer(&sender, &to, kitty_id)?;
+			Self::next_transfer_count(&sender, 1)?;
+			let fee = T::TransferFee::get();
+			if !fee.is_zero() {
+				T::Currency::transfer(&sender, &T::Treasury::get(), fee).map_err(|_| "Cannot pay transfer fee")?;
+			}
+
+			Self::do_transfer_out(&sender, &to, kitty_id, true)?;
+		}
+
+		/// Transfer several kitties to the same new owner
+		pub fn batch_transfer(origin, to: T::AccountId, kitty_ids: Vec<T::KittyIndex>) {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_not_paused(PAUSE_TRANSFER)?;
+
+			Self::ensure_batch_size(kitty_ids.len())?;
+			// Check the whole batch first so it isn't applied halfway
+			let mut deposits = BalanceOf::<T>::zero();
+			for (i, kitty_id) in kitty_ids.iter().enumerate() {
+				ensure!(!kitty_ids[..i].contains(kitty_id), "Duplicate kitty in batch");
+				Self::ensure_can_transfer(&sender, &to, *kitty_id)?;
+				deposits = deposits.saturating_add(Self::following_deposit(&to, *kitty_id));
+			}
+			ensure!(T::Currency::can_reserve(&to, deposits), "Cannot reserve kitty deposit");
+			let batch_size: T::KittyIndex = (kitty_ids.len() as u32).into();
+			Self::owned_kitties_count(&to).checked_add(&batch_size).ok_or("Owned kitties count overflow")?;
+			Self::next_transfer_count(&sender, kitty_ids.len() as u32)?;
+
+			let compact = T::CompactBatchEvents::get();
+			for kitty_id in kitty_ids.iter() {
+				Self::do_transfer_out(&sender, &to, *kitty_id, !compact)?;
+			}
+			if compact {
+				Self::deposit_event(RawEvent::BatchTransferred(sender, to, kitty_ids));
+			}
+		}
+
+		/// Approve an account to transfer a kitty on behalf of its owner
+		pub fn approve(origin, spender: T::AccountId, kitty_id: T::KittyIndex) {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(Self::kitty_owner(kitty_id).as_ref() == Some(&sender), "Not owner of kitty");
+
+			<KittyApprovals<T>>::insert(kitty_id, &spender);
+
+			Self::deposit_event(RawEvent::Approval(sender, Some(spender), kitty_id));
+		}
+
+		/// Approve an account to transfer each of the given kitties on behalf of their owner
+		pub fn approve_many(origin, spender: T::AccountId, kitty_ids: Vec<T::KittyIndex>) {
+			let sender = ensure_signed(origin)?;
+
+			Self::ensure_batch_size(kitty_ids.len())?;
+			// Check the whole batch first so it isn't applied halfway
+			for kitty_id in kitty_ids.iter() {
+				ensure!(Self::kitty_owner(kitty_id).as_ref() == Some(&sender), "Not owner of kitty");
+			}
+
+			for kitty_id in kitty_ids {
+				<KittyApprovals<T>>::insert(kitty_id, &spender);
+				Self::deposit_event(RawEvent::Approval(sender.clone(), Some(spender.clone()), kitty_id));
+			}
+		}
+
+		/// Revoke the approval to transfer a kitty on behalf of its owner
+		pub fn cancel_approval(origin, kitty_id: T::KittyIndex) {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(Self::kitty_owner(kitty_id).as_ref() == Some(&sender), "Not owner of kitty");
+			ensure!(<KittyApprovals<T>>::exists(kitty_id), "Kitty has no approval");
+
+			<KittyApprovals<T>>::remove(kitty_id);
+
+			Self::deposit_event(RawEvent::Approval(sender, None, kitty_id));
+		}
+
+		/// Allow or disallow an operator to transfer all kitties of the sender
+		pub fn set_approval_for_all(origin, operator: T::AccountId, approved: bool) {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(sender != operator, "Cannot approve self as operator");
+
+			let was_approved = Self::is_approved_for_all(&sender, &operator);
+			if approved && !was_approved {
+				let count = Self::operator_count(&sender);
+				ensure!(count < T::MaxOperatorsPerOwner::get(), "Too many operators");
+				<OperatorApprovals<T>>::insert(&sender, &operator, true);
+				<OperatorCount<T>>::insert(&sender, count + 1);
+			} else if !approved && was_approved {
+				<OperatorApprovals<T>>::remove(&sender, &operator);
+				<OperatorCount<T>>::mutate(&sender, |count| *count = count.saturating_sub(1));
+			}
+		}
+
+		/// Allow or disallow a consignor to transfer and list kitties on the sender's behalf
+		pub fn allow_consignment(origin, consignor: T::AccountId, allowed: bool) {
+			let sender = ensure_signed(origin)?;
+
+			if allowed {
+				<ConsignmentApprovals<T>>::insert(&sender, &consignor, true);
+			} else {
+				<ConsignmentApprovals<T>>::remove(&sender, &consignor);
+			}
+		}
+
+		/// Transfer a kitty to a consignee and list it for sale at `price` on their behalf
+		pub fn consign(origin, to: T::AccountId, kitty_id: T::KittyIndex, price: BalanceOf<T>) {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_not_paused(PAUSE_TRANSFER | PAUSE_TRADE)?;
+
+			ensure!(Self::accepts_consignment(&to, &sender), "Consignment not allowed");
+			ensure!(price <= T::MaxListingPrice::get(), "Price too high");
+
+			Self::do_transfer_out(&sender, &to, kitty_id, true)?;
+			<KittyPrices<T>>::insert(kitty_id, price);
+
+			Self::deposit_event(RawEvent::PriceSet(to, kitty_id, price));
+		}
+
+		/// Transfer a kitty to new owner, checking that the recipient can hold it
+		pub fn safe_transfer(origin, to: T::AccountId, kitty_id: T::KittyIndex) {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_not_paused(PAUSE_TRANSFER)?;
+
+			ensure!(!Self::is_frozen(&to), "Recipient account is frozen");
+			ensure!(Self::owned_kitties_count(&to) < T::MaxKittiesPerAccount::get(), "Recipient has too many kitties");
+
+			Self::do_transfer_out(&sender, &to, kitty_id, true)?;
+		}
+
+		/// Mint a copy of a kitty to an account, with the same generation and parents
+		///
+		/// The last DNA byte is bumped until the DNA is unique.
+		pub fn clone_kitty(origin, source_id: T::KittyIndex, to: T::AccountId) {
+			ensure_root(origin)?;
+
+			let source = Self::kitties(source_id).ok_or("Invalid source kitty")?;
+			// Tombstoned kitties are still stored, but are dead
+			ensure!(!Self::is_tombstoned(source_id), "Invalid source kitty");
+			ensure!(Self::owned_kitties_count(&to) < T::MaxKittiesPerAccount::get(), "Too many kitties owned");
+			let kitty_id = Self::next_kitty_id()?;
+
+			let mut dna = source.0;
+			let last = dna.len() - 1;
+			// Up to 256 lookups when most variants of the last byte are taken, bounded and root only
+			for _ in 0..=u8::max_value() {
+				if !<DnaExists<T>>::exists(&dna) {
+					break;
+				}
+				dna[last] = dna[last].wrapping_add(1);
+			}
+			ensure!(!<DnaExists<T>>::exists(&dna), "Kitty DNA already exists");
+
+			Self::insert_kitty(&to, kitty_id, Kitty(dna), Self::generation_of(source_id))?;
+			Self::deposit_event(RawEvent::CreatedWithDna(to.clone(), kitty_id, dna));
+			if let Some(parents) = Self::parents_of(source_id) {
+				Self::set_parents(kitty_id, parents);
+			}
+		}
+
+		/// Set the paused operations, a combination of the `PAUSE_*` flags
+		pub fn set_paused_ops(origin, flags: u8) {
+			ensure_root(origin)?;
+
+			<PausedOps>::put(flags);
+		}
+
+		/// Set the base breeding fee, or fall back to `BaseBreedingFee` with `None`
+		pub fn set_breed_fee(origin, fee: Option<BalanceOf<T>>) {
+			ensure_root(origin)?;
+
+			match fee {
+				Some(fee) => <BreedFee<T>>::put(fee),
+				None => <BreedFee<T>>::kill(),
+			}
+
+			Self::deposit_event(RawEvent::BreedFeeSet(fee));
+		}
+
+		/// Rebuild the owned kitties of an account from the kitty owners, fixing its count
+		pub fn repair_owner_count(origin, account: T::AccountId) {
+			ensure_root(origin)?;
+
+			let mut owned = Vec::new();
+			let count = Self::kitties_count();
+			let mut kitty_id = T::KittyIndex::zero();
+			while kitty_id < count {
+				if Self::kitty_owner(kitty_id).as_ref() == Some(&account) {
+					owned.push(kitty_id);
+				}
+				kitty_id = kitty_id + One::one();
+			}
+
+			let old = Self::owned_kitties_count(&account);
+			<OwnedKitties<T>>::remove((account.clone(), None));
+			let mut kitty_id = T::KittyIndex::zero();
+			while kitty_id < count {
+				<OwnedKitties<T>>::remove((account.clone(), Some(kitty_id)));
+				kitty_id = kitty_id + One::one();
+			}
+			let mut new = T::KittyIndex::zero();
+			for kitty_id in owned {
+				Self::owned_list_append(&account, kitty_id);
+				new = new + One::one();
+			}
+			<OwnedKittiesCount<T>>::insert(&account, new);
+
+			Self::deposit_event(RawEvent::OwnerCountRepaired(account, old, new));
+		}
+
+		/// Rewrite the generation of a kitty, moving it to the matching generation bucket
+		pub fn force_set_generation(origin, kitty_id: T::KittyIndex, generation: u16) {
+			ensure_root(origin)?;
+
+			ensure!(<Kitties<T>>::exists(kitty_id) && !Self::is_tombstoned(kitty_id), "Invalid kitty_id");
+			ensure!(generation <= T::MaxGeneration::get(), "Max generation reached");
+
+			Self::set_generation(kitty_id, generation);
+		}
+
+		/// Set the number of kitties an account can create during the presale
+		pub fn set_allowance(origin, account: T::AccountId, allowance: u32) {
+			ensure_root(origin)?;
+
+			if allowance == 0 {
+				<Allowlist<T>>::remove(&account);
+			} else {
+				<Allowlist<T>>::insert(&account, allowance);
+			}
+		}
+
+		/// Freeze or unfreeze an account
+		pub fn set_frozen(origin, account: T::AccountId, frozen: bool) {
+			ensure_root(origin)?;
+
+			if frozen {
+				<FrozenAccounts<T>>::insert(account, true);
+			} else {
+				<FrozenAccounts<T>>::remove(account);
+			}
+		}
+
+		/// Transfer a kitty on behalf of its owner, as the owner, the approved account or an operator
+		pub fn transfer_from(origin, from: T::AccountId, to: T::AccountId, kitty_id: T::KittyIndex) {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_not_paused(PAUSE_TRANSFER)?;
+
+			ensure!(
+				sender == from
+					|| Self::approved_of(kitty_id).as_ref() == Some(&sender)
+					|| Self::is_approved_for_all(&from, &sender),
+				"Not approved to transfer kitty"
+			);
+
+			Self::do_transfer_out(&from, &to, kitty_id, true)?;
+		}
+
+		/// Add a sire to the sender's wishlist, to be notified when it is offered
+		pub fn wish_sire(origin, sire: T::KittyIndex) {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(<Kitties<T>>::exists(sire), "Invalid sire");
+			let mut wishlist = Self::wishlist(&sender);
+			ensure!(!wishlist.contains(&sire), "Sire already on wishlist");
+			ensure!(wishlist.len() < MAX_WISHLIST_LENGTH, "Wishlist is full");
+			let mut wishers = Self::sire_wishers(sire);
+			ensure!(wishers.len() < MAX_WISHERS_PER_SIRE, "Too many wishers for sire");
+
+			wishlist.push(sire);
+			wishers.push(sender.clone());
+			<BreedWishlist<T>>::insert(&sender, wishlist);
+			<SireWishers<T>>::insert(sire, wishers);
+		}
+
+		/// Remove a sire from the sender's wishlist
+		pub fn unwish_sire(origin, sire: T::KittyIndex) {
+			let sender = ensure_signed(origin)?;
+
+			let mut wishlist = Self::wishlist(&sender);
+			let position = wishlist.iter().position(|id| *id == sire).ok_or("Sire not on wishlist")?;
+
+			wishlist.remove(position);
+			<BreedWishlist<T>>::insert(&sender, wishlist);
+			<SireWishers<T>>::mutate(sire, |wishers| wishers.retain(|who| *who != sender));
+		}
+
+		/// Offer a kitty as a sire, notifying the accounts wishing for it
+		pub fn offer_sire(origin, kitty_id: T::KittyIndex) {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(Self::kitty_owner(kitty_id) == Some(sender), "Not owner of kitty");
+			ensure!(!Self::is_sire_offered(kitty_id), "Sire is already offered");
+
+			<SireOffers<T>>::insert(kitty_id, true);
+			Self::notify_wishers(kitty_id);
+		}
+
+		/// Halve the remaining breeding cooldown of a kitty, paying a breeding token or `SpeedupFee`
+		pub fn use_speedup(origin, kitty_id: T::KittyIndex) {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(Self::kitty_owner(kitty_id).as_ref() == Some(&sender), "Not owner of kitty");
+			let now = <system::Module<T>>::block_number();
+			let cooldown_end = Self::cooldown_end_of(kitty_id);
+			ensure!(cooldown_end > now, "Kitty is not on cooldown");
+
+			let tokens = Self::tokens(&sender);
+			if tokens > 0 {
+				<BreedingTokens<T>>::insert(&sender, tokens - 1);
+			} else {
+				let fee = T::SpeedupFee::get();
+				if !fee.is_zero() {
+					T::Currency::transfer(&sender, &T::Treasury::get(), fee).map_err(|_| "Cannot pay speedup fee")?;
+				}
+			}
+
+			let two: T::BlockNumber = 2.into();
+			let cooldown_end = now + (cooldown_end - now) / two;
+			<KittyCooldownEnd<T>>::insert(kitty_id, cooldown_end);
+
+			Self::deposit_event(RawEvent::SpedUp(kitty_id, cooldown_end));
+		}
+
+		/// Spend a breeding token to mutate one DNA byte of a kitty
+		pub fn spend_breeding_token(origin, kitty_id: T::KittyIndex) {
+			let sender = ensure_signed(origin)?;
+
+			let kitty = Self::kitties(kitty_id).ok_or("Invalid kitty_id")?;
+			ensure!(Self::kitty_owner(kitty_id).as_ref() == Some(&sender), "Not owner of kitty");
+			ensure!(!Self::is_locked(kitty_id), "Kitty is locked");
+			ensure!(!<KittyPrices<T>>::exists(kitty_id), "Kitty is listed for sale");
+			let tokens = Self::tokens(&sender).checked_sub(1).ok_or("No breeding token")?;
+
+			let new_dna = mutate_dna(kitty.0, Self::random_value(MUTATE_SUBJECT, &sender));
+			ensure!(!<DnaExists<T>>::exists(&new_dna), "Kitty DNA already exists");
+
+			<BreedingTokens<T>>::insert(&sender, tokens);
+			Self::remove_dna_index(kitty_id, &kitty.0);
+			<DnaExists<T>>::insert(new_dna, kitty_id);
+			<Kitties<T>>::insert(kitty_id, Kitty(new_dna));
+
+			Self::deposit_event(RawEvent::Mutated(kitty_id));
+		}
+
+		/// Name a kitty, an empty name removes it
+		pub fn set_name(origin, kitty_id: T::KittyIndex, name: Vec<u8>) {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(Self::kitty_owner(kitty_id).as_ref() == Some(&sender), "Not owner of kitty");
+			ensure!(name.len() <= MAX_NAME_LENGTH, "Name too long");
+			let unique = T::UniqueNames::get();
+			if unique && !name.is_empty() {
+				ensure!(Self::name_owner(&name).map_or(true, |owner| owner == kitty_id), "Name taken");
+			}
+
+			Self::clear_name(kitty_id);
+			let mut history = Self::name_history_of(kitty_id);
+			if history.len() >= MAX_NAME_HISTORY {
+				history.remove(0);
+			}
+			history.push((name.clone(), <system::Module<T>>::block_number()));
+			<KittyNameHistory<T>>::insert(kitty_id, history);
+			if !name.is_empty() {
+				if unique {
+					<NameOwner<T>>::insert(&name, kitty_id);
+				}
+				<KittyNames<T>>::insert(kitty_id, name);
+			}
+		}
+
+		/// Burn two kitties to mint a single kitty combining their DNA
+		pub fn fuse(origin, kitty_id_1: T::KittyIndex, kitty_id_2: T::KittyIndex) {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_not_paused(PAUSE_MINT)?;
+
+			let kitty1 = Self::kitties(kitty_id_1).ok_or("Invalid kitty_id_1")?;
+			let kitty2 = Self::kitties(kitty_id_2).ok_or("Invalid kitty_id_2")?;
+			ensure!(kitty_id_1 != kitty_id_2, "Needs different kitties");
+			ensure!(Self::kitty_owner(kitty_id_1).as_ref() == Some(&sender), "Not owner of kitty_id_1");
+			ensure!(Self::kitty_owner(kitty_id_2).as_ref() == Some(&sender), "Not owner of kitty_id_2");
+			ensure!(!Self::is_locked(kitty_id_1) && !Self::is_locked(kitty_id_2), "Kitty is locked");
+
+			let generation = Self::generation_of(kitty_id_1).max(Self::generation_of(kitty_id_2)).checked_add(1)
+				.filter(|generation| *generation <= T::MaxGeneration::get())
+				.ok_or("Max generation reached")?;
+			let kitty_id = Self::next_kitty_id()?;
+
+			// The same pair always fuses into the same kitty
+			let dna = fuse_dna(&kitty1.0, &kitty2.0);
+			ensure!(
+				Self::kitties_id_by_dna(dna).map_or(true, |id| id == kitty_id_1 || id == kitty_id_2),
+				"Kitty DNA already exists"
+			);
+
+			Self::remove_kitty(&sender, kitty_id_1, &kitty1)?;
+			Self::remove_kitty(&sender, kitty_id_2, &kitty2)?;
+
+			Self::insert_kitty(&sender, kitty_id, Kitty(dna), generation)?;
+			Self::deposit_event(RawEvent::CreatedWithDna(sender.clone(), kitty_id, dna));
+			Self::set_parents(kitty_id, (kitty_id_1, kitty_id_2));
+
+			Self::deposit_event(RawEvent::Fused(sender, kitty_id_1, kitty_id_2, kitty_id));
+		}
+
+		/// Change the license tier of a kitty, paying `LicenseUpgradeFee` for each tier gained
+		pub fn set_license(origin, kitty_id: T::KittyIndex, tier: u8) {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(Self::kitty_owner(kitty_id).as_ref() == Some(&sender), "Not owner of kitty");
+			ensure!(tier <= T::MaxLicenseTier::get(), "License tier too high");
+
+			let upgrade = tier.saturating_sub(Self::license_of(kitty_id));
+			let fee = T::LicenseUpgradeFee::get().saturating_mul((upgrade as u32).into());
+			if !fee.is_zero() {
+				T::Currency::transfer(&sender, &T::Treasury::get(), fee).map_err(|_| "Cannot pay license fee")?;
+			}
+
+			<KittyLicense<T>>::insert(kitty_id, tier);
+		}
+
+		/// Lock a kitty against transfer until the given block, extending any earlier lock
+		pub fn lock_until(origin, kitty_id: T::KittyIndex, block: T::BlockNumber) {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(Self::kitty_owner(kitty_id).as_ref() == Some(&sender), "Not owner of kitty");
+			// Auctions lock the kitty until they are settled
+			ensure!(!Self::is_locked(kitty_id), "Kitty is locked");
+			ensure!(block >= Self::time_locked_until(kitty_id), "Cannot shorten time lock");
+
+			<KittyTimeLocks<T>>::insert(kitty_id, block);
+
+			Self::deposit_event(RawEvent::TimeLocked(kitty_id, block));
+		}
+
+		/// Permanently retire a kitty from breeding, this cannot be undone
+		pub fn retire(origin, kitty_id: T::KittyIndex) {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(Self::kitty_owner(kitty_id) == Some(sender), "Not owner of kitty");
+			ensure!(!Self::is_retired(kitty_id), "Kitty is already retired");
+
+			<RetiredKitties<T>>::insert(kitty_id, true);
+
+			Self::deposit_event(RawEvent::Retired(kitty_id));
+		}
+
+		/// Destroy a kitty, the owner is paid `BurnReward` if the treasury can afford it
+		pub fn burn(origin, kitty_id: T::KittyIndex) {
+			let sender = ensure_signed(origin)?;
+
+			let kitty = Self::kitties(kitty_id).ok_or("Invalid kitty_id")?;
+			ensure!(Self::kitty_owner(kitty_id).as_ref() == Some(&sender), "Not owner of kitty");
+			ensure!(!Self::is_locked(kitty_id), "Kitty is locked");
+
+			Self::remove_kitty(&sender, kitty_id, &kitty)?;
+
+			// The kitty is burned even when the treasury cannot pay the reward
+			let reward = T::BurnReward::get();
+			let rewarded = !reward.is_zero()
+				&& T::Currency::transfer(&T::Treasury::get(), &sender, reward).is_ok();
+
+			Self::deposit_event(RawEvent::Burned(sender, kitty_id, if rewarded { reward } else { Zero::zero() }));
+		}
+
+		/// Burn the given kitties to raise the level of the target by `LevelsPerFodder` for each
+		pub fn sacrifice(origin, target_id: T::KittyIndex, fodder_ids: Vec<T::KittyIndex>) {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(Self::kitty_owner(target_id).as_ref() == Some(&sender), "Not owner of kitty");
+			ensure!(!fodder_ids.is_empty(), "No fodder kitties");
+			Self::ensure_batch_size(fodder_ids.len())?;
+			// Check all the fodder first so it isn't burned halfway
+			let mut fodder = Vec::new();
+			for (i, kitty_id) in fodder_ids.iter().enumerate() {
+				ensure!(*kitty_id != target_id, "Cannot sacrifice the target");
+				ensure!(!fodder_ids[..i].contains(kitty_id), "Duplicate kitty in batch");
+				let kitty = Self::kitties(kitty_id).ok_or("Invalid kitty_id")?;
+				ensure!(Self::kitty_owner(kitty_id).as_ref() == Some(&sender), "Not owner of fodder");
+				ensure!(!Self::is_locked(*kitty_id), "Kitty is locked");
+				fodder.push((*kitty_id, kitty));
+			}
+
+			for (kitty_id, kitty) in fodder.iter() {
+				Self::remove_kitty(&sender, *kitty_id, kitty)?;
+			}
+			let gained = T::LevelsPerFodder::get().saturating_mul(fodder.len() as u32);
+			let level = Self::level_of(target_id).saturating_add(gained);
+			<KittyLevel<T>>::insert(target_id, level);
+
+			Self::deposit_event(RawEvent::Upgraded(sender, target_id, gained, level));
+		}
+
+		/// List a kitty for sale at the given price, or remove the listing with `None`
+		pub fn set_price(origin, kitty_id: T::KittyIndex, price: Option<BalanceOf<T>>) {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_not_paused(PAUSE_TRADE)?;
+
+			ensure!(Self::kitty_owner(kitty_id).as_ref() == Some(&sender), "Not owner of kitty");
+			ensure!(!<Auctions<T>>::exists(kitty_id), "Kitty is on auction");
+
+			if let Some(price) = price {
+				ensure!(price <= T::MaxListingPrice::get(), "Price too high");
+				<KittyPrices<T>>::insert(kitty_id, price);
+
+				Self::deposit_event(RawEvent::PriceSet(sender, kitty_id, price));
+			} else {
+				ensure!(<KittyPrices<T>>::exists(kitty_id), "Kitty is not listed");
+
+				<KittyPrices<T>>::remove(kitty_id);
+
+				Self::deposit_event(RawEvent::Unlisted(kitty_id));
+			}
+		}
+
+		/// Buy a kitty listed for sale, paying its price to the owner as long as it is at most `max_price`
+		pub fn buy(origin, kitty_id: T::KittyIndex, max_price: BalanceOf<T>) {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_not_paused(PAUSE_TRANSFER | PAUSE_TRADE)?;
+
+			let price = Self::kitty_price(kitty_id).ok_or("Kitty is not for sale")?;
+			ensure!(price <= max_price, "Price above max price");
+			let seller = Self::kitty_owner(kitty_id).ok_or("Invalid kitty_id")?;
+			ensure!(seller != sender, "Cannot buy own kitty");
+			// Check the transfer before paying, a failed dispatch doesn't revert the payment
+			Self::ensure_can_transfer(&seller, &sender, kitty_id)?;
+			Self::next_transfer_count(&seller, 1)?;
+			ensure!(
+				T::Currency::free_balance(&sender) >= price.saturating_add(Self::following_deposit(&sender, kitty_id)),
+				"Cannot pay kitty price"
+			);
+
+			T::Currency::transfer(&sender, &seller, price).map_err(|_| "Cannot pay kitty price")?;
+			<KittyPrices<T>>::remove(kitty_id);
+			Self::do_transfer_out(&seller, &sender, kitty_id, true)?;
+
+			Self::deposit_event(RawEvent::Sold(sender, seller, kitty_id, price));
+		}
+
+		/// Put a kitty up for auction, accepting bids for `duration` blocks
+		pub fn create_auction(origin, kitty_id: T::KittyIndex, start_price: BalanceOf<T>, duration: T::BlockNumber) {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_not_paused(PAUSE_TRADE)?;
+
+			ensure!(Self::kitty_owner(kitty_id).as_ref() == Some(&sender), "Not owner of kitty");
+			ensure!(!Self::is_locked(kitty_id), "Kitty is locked");
+			let now = <system::Module<T>>::block_number();
+			ensure!(now >= Self::time_locked_until(kitty_id), "Kitty is time locked");
+			ensure!(!<KittyPrices<T>>::exists(kitty_id), "Kitty is listed for sale");
+			ensure!(!duration.is_zero(), "Auction duration must not be zero");
+			ensure!(start_price <= T::MaxListingPrice::get(), "Price too high");
+
+			let end_block = now + duration;
+			let mut ending = Self::auctions_ending_at(end_block);
+			ensure!(ending.len() < MAX_AUCTIONS_PER_BLOCK, "Too many auctions ending at this block");
+
+			ending.push(kitty_id);
+			<AuctionsEndingAt<T>>::insert(end_block, ending);
+			<KittyLocks<T>>::insert(kitty_id, true);
+			<Auctions<T>>::insert(kitty_id, Auction {
+				seller: sender.clone(),
+				start_price,
+				end_block,
+				highest_bid: None,
+			});
+
+			Self::deposit_event(RawEvent::AuctionCreated(sender, kitty_id, start_price, end_block));
+		}
+
+		/// Bid on a kitty auction, the amount is reserved until the sender is outbid
+		pub fn bid(origin, kitty_id: T::KittyIndex, amount: BalanceOf<T>) {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_not_paused(PAUSE_TRADE)?;
+
+			let mut auction = Self::auction_of(kitty_id).ok_or("Kitty is not on auction")?;
+			ensure!(<system::Module<T>>::block_number() < auction.end_block, "Auction has ended");
+			ensure!(sender != auction.seller, "Cannot bid on own auction");
+			ensure!(amount >= auction.start_price, "Bid below start price");
+			if let Some((_, highest)) = &auction.highest_bid {
+				ensure!(amount > *highest, "Bid not higher than current bid");
+				ensure!(amount >= highest.saturating_add(T::MinBidIncrement::get()), "Bid increment too small");
+			}
+
+			T::Currency::reserve(&sender, amount)?;
+			if let Some((bidder, highest)) = auction.highest_bid.take() {
+				T::Currency::unreserve(&bidder, highest);
+			}
+
+			// The sweep never cancels an auction with bids, so its expiry bucket needn't move
+			let window = T::AntiSnipeWindow::get();
+			let extended = !window.is_zero() && <system::Module<T>>::block_number() + window >= auction.end_block;
+			if extended {
+				auction.end_block = auction.end_block + window;
+			}
+			let end_block = auction.end_block;
+
+			auction.highest_bid = Some((sender.clone(), amount));
+			<Auctions<T>>::insert(kitty_id, auction);
+
+			Self::deposit_event(RawEvent::BidPlaced(sender, kitty_id, amount));
+			if extended {
+				Self::deposit_event(RawEvent::AuctionExtended(kitty_id, end_block));
+			}
+		}
+
+		/// Settle an ended auction, handing the kitty to the highest bidder
+		pub fn close_auction(origin, kitty_id: T::KittyIndex) {
+			ensure_signed(origin)?;
+
+			let auction = Self::auction_of(kitty_id).ok_or("Kitty is not on auction")?;
+			ensure!(<system::Module<T>>::block_number() >= auction.end_block, "Auction has not ended");
+
+			if let Some((bidder, amount)) = auction.highest_bid {
+				// The kitty stays locked until the auction is removed, so run the transfer checks here
+				ensure!(Self::kitty_owner(kitty_id).as_ref() == Some(&auction.seller), "Not owner of kitty");
+				Self::ensure_can_receive(&bidder, kitty_id)?;
+				// Settling counts as a transfer out of the seller, like `do_transfer_out`
+				let transfer_count = Self::next_transfer_count(&auction.seller, 1)?;
+				// Repatriating to an account that doesn't exist fails
+				ensure!(!T::Currency::total_balance(&auction.seller).is_zero(), "Seller account doesn't exist");
+
+				T::Currency::repatriate_reserved(&bidder, &auction.seller, amount)?;
+				Self::move_kitty(&auction.seller, &bidder, kitty_id, true)?;
+				<TransferCount<T>>::insert(&auction.seller, transfer_count);
+				Self::remove_auction(kitty_id, auction.end_block);
+
+				Self::deposit_event(RawEvent::AuctionClosed(kitty_id, bidder, amount));
+			} else {
+				Self::remove_auction(kitty_id, auction.end_block);
+				Self::deposit_event(RawEvent::AuctionCancelled(kitty_id));
+			}
+		}
+	}
+}
+
+decl_event!(
+	pub enum Event<T> where
+		AccountId = <T as system::Trait>::AccountId,
+		KittyIndex = <T as Trait>::KittyIndex,
+		Balance = BalanceOf<T>,
+		BlockNumber = <T as system::Trait>::BlockNumber,
+	{
+		/// The owner approved an account to transfer a kitty, or revoked the approval with `None`.
+		Approval(AccountId, Option<AccountId>, KittyIndex),
+		/// A kitty was minted or bred to an account, with its DNA.
+		CreatedWithDna(AccountId, KittyIndex, [u8; 16]),
+		/// A kitty was transferred from the first to the second account.
+		Transferred(AccountId, AccountId, KittyIndex),
+		/// Two kitties of an account were bred into the third kitty, with the selector combining their DNA
+		/// and the bits flipped after by the gender, donor genes and locked traits.
+		Bred(AccountId, KittyIndex, KittyIndex, KittyIndex, [u8; 16], [u8; 16]),
+		/// A breed scheduled by an account no longer passed the breeding checks and was dropped.
+		ScheduledBreedFailed(AccountId, KittyIndex, KittyIndex),
+		/// A kitty was listed for sale at the given price.
+		PriceSet(AccountId, KittyIndex, Balance),
+		/// A kitty was removed from sale.
+		Unlisted(KittyIndex),
+		/// The first account bought a kitty listed by the second account at the given price.
+		Sold(AccountId, AccountId, KittyIndex, Balance),
+		/// A kitty was retired from breeding.
+		Retired(KittyIndex),
+		/// A kitty was put up for auction with a start price, ending at the given block.
+		AuctionCreated(AccountId, KittyIndex, Balance, BlockNumber),
+		/// A bid was placed on a kitty auction.
+		BidPlaced(AccountId, KittyIndex, Balance),
+		/// A kitty auction was won by the given bidder for the given amount.
+		AuctionClosed(KittyIndex, AccountId, Balance),
+		/// A kitty auction ended without bids.
+		AuctionCancelled(KittyIndex),
+		/// A late bid pushed the end of a kitty auction back to the given block.
+		AuctionExtended(KittyIndex, BlockNumber),
+		/// A kitty was burned by its owner, who was paid the given reward.
+		Burned(AccountId, KittyIndex, Balance),
+		/// A sire was offered, naming the accounts having it on their wishlist.
+		SireAvailable(KittyIndex, Vec<AccountId>),
+		/// A breeding token was spent to mutate a kitty.
+		Mutated(KittyIndex),
+		/// A kitty with the given DNA was put in the incubator by its creator.
+		Incubated(AccountId, KittyIndex, [u8; 16]),
+		/// An incubating kitty was hatched by its new owner.
+		Hatched(AccountId, KittyIndex),
+		/// The generation of an existing kitty was rewritten from the first to the second value.
+		GenerationChanged(KittyIndex, u16, u16),
+		/// The breeding cooldown of a kitty was sped up to end at the given block.
+		SpedUp(KittyIndex, BlockNumber),
+		/// The owned kitties count of an account was repaired from the first to the second value.
+		OwnerCountRepaired(AccountId, KittyIndex, KittyIndex),
+		/// An account created the given kitties in a batch.
+		BatchCreated(AccountId, Vec<KittyIndex>),
+		/// The given kitties were transferred in a batch from the first to the second account.
+		BatchTransferred(AccountId, AccountId, Vec<KittyIndex>),
+		/// The owner fused the first two kitties into the third one.
+		Fused(AccountId, KittyIndex, KittyIndex, KittyIndex),
+		/// Random values were drawn without a random seed and are predictable.
+		InsecureRandomness(BlockNumber),
+		/// The owner sacrificed kitties to a kitty, raising it by the first number of levels to the second.
+		Upgraded(AccountId, KittyIndex, u32, u32),
+		/// The owner locked a kitty against transfer until the given block.
+		TimeLocked(KittyIndex, BlockNumber),
+		/// Root set the base breeding fee, `None` falling back to `BaseBreedingFee`.
+		BreedFeeSet(Option<Balance>),
+	}
+);
+
+fn gender_from_byte(byte: u8) -> Gender {
+	if byte & 1 == 0 {
+		Gender::Male
+	} else {
+		Gender::Female
+	}
+}
+
+fn combine_dna(dna1: u8, dna2: u8, selector: u8) -> u8 {
+	(selector & dna1) | (!selector & dna2)
+}
+
+/// Counts the bits that differ between two DNAs.
+pub fn dna_distance(a: &[u8; 16], b: &[u8; 16]) -> u32 {
+	a.iter().zip(b.iter()).map(|(x, y)| (x ^ y).count_ones()).sum()
+}
+
+/// Values a kitty for marketplaces to suggest a price from.
+///
+/// The value is `1000 * (1 + rarity) / ((1 + generation) * (1 + children))`, rounded down
+/// and halved again for retired kitties, which can't breed anymore.
+pub fn appraisal(rarity: u32, generation: u16, children: u32, retired: bool) -> u64 {
+	let value = 1000 * (1 + u64::from(rarity)) / ((1 + u64::from(generation)) * (1 + u64::from(children)));
+	if retired {
+		value / 2
+	} else {
+		value
+	}
+}
+
+/// Replaces one DNA byte with a random value, always changing it.
+///
+/// The first byte holding the gender bit is never mutated.
+pub fn mutate_dna(dna: [u8; 16], random: [u8; 16]) -> [u8; 16] {
+	let mut new_dna = dna;
+	let index = 1 + random[0] as usize % (dna.len() - 1);
+	new_dna[index] = if random[1] == dna[index] { !random[1] } else { random[1] };
+	new_dna
+}
+
+/// Combines the DNA of two fused kitties, using a selector derived from both of them.
+pub fn fuse_dna(dna1: &[u8; 16], dna2: &[u8; 16]) -> [u8; 16] {
+	let selector = (dna1, dna2).using_encoded(blake2_128);
+	let mut dna = [0u8; 16];
+	for i in 0..dna.len() {
+		dna[i] = combine_dna(dna1[i], dna2[i], selector[i]);
+	}
+	dna
+}
+
+/// Decides the gender of an offspring from the DNA of both parents and the breeding selector.
+///
+/// The same parents and selector always give the same gender, so breeding outcomes are
+/// reproducible from their inputs.
+pub fn offspring_gender(p1: &[u8; 16], p2: &[u8; 16], selector: &[u8; 16]) -> Gender {
+	let seed = (p1, p2, selector).using_encoded(blake2_128);
+	gender_from_byte(seed[0])
+}
+
+/// Decides the gender of an offspring from the `(male, female)` counts of the population.
+///
+/// The minority gender is picked with a chance equal to the share of the majority, so the
+/// population drifts back towards an even ratio. `entropy` picks the gender of the first kitty.
+pub fn balanced_gender(counts: (u64, u64), entropy: u8) -> Gender {
+	let (males, females) = counts;
+	let total = males.saturating_add(females);
+	if total == 0 {
+		return gender_from_byte(entropy);
+	}
+	// Female when `entropy / 256 < males / total`
+	if (entropy as u128) * (total as u128) < (males as u128) << 8 {
+		Gender::Female
+	} else {
+		Gender::Male
+	}
+}
+
+/// Orders the ids of two parents, so both orders count as the same pair.
+fn pair_key<I: Ord>(kitty_id_1: I, kitty_id_2: I) -> (I, I) {
+	if kitty_id_1 <= kitty_id_2 {
+		(kitty_id_1, kitty_id_2)
+	} else {
+		(kitty_id_2, kitty_id_1)
+	}
+}
+
+/// Derives the breeding selector of two parents from a random value.
+///
+/// The ids and DNA of both parents are hashed with the random value, so different pairings
+/// get different selectors from the same entropy.
+pub fn parent_selector<I: Encode>(random: [u8; 16], parent_1: (I, &[u8; 16]), parent_2: (I, &[u8; 16])) -> [u8; 16] {
+	(random, parent_1, parent_2).using_encoded(blake2_128)
+}
+
+/// Skews a random breeding selector towards the rarer of two parents.
+///
+/// Selector bits set to 1 take the gene of the first parent. The first
+/// `16 * |p1_rarity - p2_rarity| / (p1_rarity + p2_rarity)` bytes are taken entirely
+/// from the rarer parent and the remaining bytes keep the random selector, so parents
+/// of equal rarity get the random selector unchanged.
+pub fn biased_selector(p1_rarity: u32, p2_rarity: u32, random: [u8; 16]) -> [u8; 16] {
+	let total = u64::from(p1_rarity) + u64::from(p2_rarity);
+	if p1_rarity == p2_rarity || total == 0 {
+		return random;
+	}
+
+	let difference = u64::from(p1_rarity.max(p2_rarity) - p1_rarity.min(p2_rarity));
+	let biased_bytes = (difference * random.len() as u64 / total) as usize;
+	let rarer_parent = if p1_rarity > p2_rarity { 0xff } else { 0x00 };
+
+	let mut selector = random;
+	for byte in selector.iter_mut().take(biased_bytes) {
+		*byte = rarer_parent;
+	}
+	selector
+}
+
+impl<T: Trait> Module<T> {
+	/// Get the kitties with the given ids, in the same order and `None` for missing ids.
+	///
+	/// Only the first `MAX_QUERY_LENGTH` ids are looked up.
+	pub fn kitties_by_ids(ids: Vec<T::KittyIndex>) -> Vec<Option<Kitty>> {
+		ids.into_iter().take(MAX_QUERY_LENGTH).map(|id| Self::kitties(id)).collect()
+	}
+
+	/// The id given to the first kitty, at genesis or on the first mint.
+	pub fn first_kitty_id() -> T::KittyIndex {
+		Zero::zero()
+	}
+
+	/// Describe the kitty index type.
+	pub fn kitty_index_info() -> KittyIndexInfo<T::KittyIndex> {
+		KittyIndexInfo {
+			first_id: Self::first_kitty_id(),
+			bits: (rstd::mem::size_of::<T::KittyIndex>() * 8) as u32,
+		}
+	}
+
+	/// Get the render traits of a kitty, see `Kitty::render_traits`.
+	pub fn kitties_render_traits(kitty_id: T::KittyIndex) -> Option<Vec<u8>> {
+		Self::kitties(kitty_id).map(|kitty| kitty.render_traits())
+	}
+
+	/// Score the bloodline of a kitty, `None` if it doesn't exist.
+	///
+	/// The rarity of the kitty weighs `2^MAX_PEDIGREE_DEPTH`, and each generation of
+	/// ancestors weighs half the one below, down to 1 for the oldest counted generation.
+	pub fn pedigree_score(kitty_id: T::KittyIndex) -> Option<u32> {
+		let mut score = Self::kitties(kitty_id)?.rarity() << MAX_PEDIGREE_DEPTH;
+		let mut generation = Vec::new();
+		if let Some((p1, p2)) = Self::parents_of(kitty_id) {
+			generation.push(p1);
+			generation.push(p2);
+		}
+		for depth in 1..=MAX_PEDIGREE_DEPTH {
+			let mut parents = Vec::new();
+			for ancestor in generation {
+				if let Some(kitty) = Self::kitties(ancestor) {
+					score = score.saturating_add(kitty.rarity() << (MAX_PEDIGREE_DEPTH - depth));
+				}
+				if let Some((p1, p2)) = Self::parents_of(ancestor) {
+					parents.push(p1);
+					parents.push(p2);
+				}
+			}
+			generation = parents;
+		}
+		Some(score)
+	}
+
+	/// Get the existing kitties sharing at least one recorded parent with a kitty, by increasing id.
+	///
+	/// Only the children of the two parents are scanned, at most `MAX_QUERY_LENGTH` siblings
+	/// are returned.
+	pub fn siblings_of(kitty_id: T::KittyIndex) -> Vec<T::KittyIndex> {
+		let (p1, p2) = match Self::parents_of(kitty_id) {
+			Some(parents) => parents,
+			None => return Vec::new(),
+		};
+		let mut siblings = Self::children_of(p1);
+		if p2 != p1 {
+			siblings.extend(Self::children_of(p2));
+		}
+		siblings.sort();
+		siblings.dedup();
+		siblings.into_iter()
+			.filter(|sibling| *sibling != kitty_id && <Kitties<T>>::exists(sibling))
+			.take(MAX_QUERY_LENGTH)
+			.collect()
+	}
+
+	/// Check whether the owner of the first kitty can breed it with the second one.
+	pub fn breeding_compatibility(
+		kitty_id_1: T::KittyIndex,
+		kitty_id_2: T::KittyIndex,
+	) -> CompatibilityReport<T::BlockNumber> {
+		let reason = match Self::kitty_owner(kitty_id_1) {
+			Some(owner) => Self::ensure_can_breed(&owner, kitty_id_1, kitty_id_2).err(),
+			None => Some(Incompatibility::InvalidKitty),
+		};
+
+		let now = <system::Module<T>>::block_number();
+		let cooldown_end = [kitty_id_1, kitty_id_2].iter()
+			.filter(|kitty_id| Self::has_cooldown(**kitty_id))
+			.map(|kitty_id| Self::cooldown_end_of(kitty_id))
+			.max()
+			.filter(|end| *end > now);
+
+		CompatibilityReport {
+			can_breed: reason.is_none(),
+			reason,
+			cooldown_remaining: cooldown_end.map(|end| end - now),
+			dna_distance: Self::dna_distance_between(kitty_id_1, kitty_id_2).unwrap_or(0),
+		}
+	}
+
+	/// Get the DNA distance between two kitties, `None` if either doesn't exist.
+	pub fn dna_distance_between(kitty_id_1: T::KittyIndex, kitty_id_2: T::KittyIndex) -> Option<u32> {
+		let kitty1 = Self::kitties(kitty_id_1)?;
+		let kitty2 = Self::kitties(kitty_id_2)?;
+		Some(dna_distance(&kitty1.0, &kitty2.0))
+	}
+
+	/// Get the owners of the given kitties, in the same order and `None` for missing ids.
+	///
+	/// At most `MAX_QUERY_LENGTH` ids are looked up.
+	pub fn owners_of(ids: Vec<T::KittyIndex>) -> Vec<Option<T::AccountId>> {
+		ids.into_iter().take(MAX_QUERY_LENGTH).map(|id| Self::kitty_owner(id)).collect()
+	}
+
+	/// Page through the existing kitties with their owners, from id `start` and by increasing id.
+	///
+	/// At most `limit` kitties are returned, capped at `MAX_QUERY_LENGTH`. The next page starts
+	/// after the last returned id.
+	pub fn kitties_snapshot(start: T::KittyIndex, limit: u32) -> Vec<(T::KittyIndex, Kitty, T::AccountId)> {
+		let limit = (limit as usize).min(MAX_QUERY_LENGTH);
+		let count = Self::kitties_count();
+		let mut snapshot = Vec::new();
+		let mut kitty_id = start;
+		while kitty_id < count && snapshot.len() < limit {
+			if let (Some(kitty), Some(owner)) = (Self::kitties(kitty_id), Self::kitty_owner(kitty_id)) {
+				snapshot.push((kitty_id, kitty, owner));
+			}
+			kitty_id = kitty_id + One::one();
+		}
+		snapshot
+	}
+
+	/// Page through the kitties listed for sale with their owner and price, by increasing id.
+	///
+	/// Scans the ids from `start` and returns at most `limit` listings, capped at `MAX_QUERY_LENGTH`.
+	pub fn active_listings(start: T::KittyIndex, limit: u32) -> Vec<(T::KittyIndex, T::AccountId, BalanceOf<T>)> {
+		let limit = (limit as usize).min(MAX_QUERY_LENGTH);
+		let count = Self::kitties_count();
+		let mut listings = Vec::new();
+		let mut kitty_id = start;
+		while kitty_id < count && listings.len() < limit {
+			if let (Some(price), Some(owner)) = (Self::kitty_price(kitty_id), Self::kitty_owner(kitty_id)) {
+				listings.push((kitty_id, owner, price));
+			}
+			kitty_id = kitty_id + One::one();
+		}
+		listings
+	}
+
+	/// Appraise a kitty, see `appraisal`, `None` if it doesn't exist.
+	pub fn appraise(kitty_id: T::KittyIndex) -> Option<u64> {
+		let kitty = Self::kitties(kitty_id)?;
+		let children = Self::children_of(kitty_id).len() as u32;
+		Some(appraisal(kitty.rarity(), Self::generation_of(kitty_id), children, Self::is_retired(kitty_id)))
+	}
+
+	/// Get the number of living male and female kitties, from the maintained `GenderCounts`.
+	pub fn gender_distribution() -> (u32, u32) {
+		let (males, females) = Self::gender_counts();
+		let saturate = |count: u64| count.min(u64::from(u32::max_value())) as u32;
+		(saturate(males), saturate(females))
+	}
+
+	/// Get the kitties owned by an account with their sale price, `None` for unlisted kitties.
+	///
+	/// At most `MAX_QUERY_LENGTH` kitties are returned, in the order of the owner's kitty list.
+	pub fn owned_with_prices(owner: &T::AccountId) -> Vec<(T::KittyIndex, Option<BalanceOf<T>>)> {
+		Self::kitties_of(owner).into_iter()
+			.take(MAX_QUERY_LENGTH)
+			.map(|kitty_id| (kitty_id, Self::kitty_price(kitty_id)))
+			.collect()
+	}
+
+	/// Get the cheapest kitty listed for sale with its price, optionally of the given generation only.
+	///
+	/// Equal prices are broken by the lowest id. This scans every kitty and is meant for runtime
+	/// API queries.
+	pub fn floor_price(generation: Option<u16>) -> Option<(T::KittyIndex, BalanceOf<T>)> {
+		let count = Self::kitties_count();
+		let mut floor: Option<(T::KittyIndex, BalanceOf<T>)> = None;
+		let mut kitty_id = T::KittyIndex::zero();
+		while kitty_id < count {
+			if let Some(price) = Self::kitty_price(kitty_id) {
+				let matches = generation.map_or(true, |generation| Self::generation_of(kitty_id) == generation);
+				if matches && floor.map_or(true, |(_, lowest)| price < lowest) {
+					floor = Some((kitty_id, price));
+				}
+			}
+			kitty_id = kitty_id + One::one();
+		}
+		floor
+	}
+
+	/// Sum the funds reserved by this module, the egg and creation deposits and the highest auction bids.
+	///
+	/// This scans every kitty and is meant for runtime API queries.
+	pub fn total_reserved() -> BalanceOf<T> {
+		let count = Self::kitties_count();
+		let mut total = Self::total_egg_deposits();
+		let mut kitty_id = T::KittyIndex::zero();
+		while kitty_id < count {
+			if let Some((_, amount)) = Self::auction_of(kitty_id).and_then(|auction| auction.highest_bid) {
+				total = total.saturating_add(amount);
+			}
+			if let Some((_, deposit)) = Self::deposit_of(kitty_id) {
+				total = total.saturating_add(deposit);
+			}
+			kitty_id = kitty_id + One::one();
+		}
+		total
+	}
+
+	/// Rank the kitties from rarest to most common, kitties of equal rarity by id.
+	///
+	/// Only the first `MAX_RANKING_LENGTH` kitties are kept. This scans every kitty and is
+	/// meant to be run by the offchain worker rather than on chain.
+	pub fn rarity_ranking() -> Vec<T::KittyIndex> {
+		let count = Self::kitties_count();
+		let mut rarities = Vec::new();
+		let mut kitty_id = T::KittyIndex::zero();
+		while kitty_id < count {
+			if let Some(kitty) = Self::kitties(kitty_id) {
+				rarities.push((kitty.rarity(), kitty_id));
+			}
+			kitty_id = kitty_id + One::one();
+		}
+
+		rarities.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+		rarities.into_iter().take(MAX_RANKING_LENGTH).map(|(_, kitty_id)| kitty_id).collect()
+	}
+
+	/// Write the rarity ranking at `now` to the offchain persistent storage and submit it on chain.
+	fn publish_rarity_ranking(now: T::BlockNumber) {
+		let ranking = Self::rarity_ranking();
+		runtime_io::local_storage_set(StorageKind::PERSISTENT, RARITY_RANKING_KEY, &ranking.encode());
+
+		// The local ranking is kept when the transaction can't be built or is rejected by the pool
+		let call = Call::<T>::submit_rarity_ranking(ranking, now);
+		if let Some(extrinsic) = T::UncheckedExtrinsic::new_unsigned(call.into()) {
+			let _ = runtime_io::submit_transaction(&extrinsic);
+		}
+	}
+
+	/// Check a rarity ranking submitted for block `at`.
+	///
+	/// The ranking must be newer than the stored one, due at `at` and in rarity order. Whether it
+	/// includes every kitty is not checked, as that needs the full scan the worker saves.
+	fn ensure_valid_ranking(ranking: &[T::KittyIndex], at: T::BlockNumber) -> Result {
+		let interval = T::RankingInterval::get();
+		ensure!(!interval.is_zero() && (at % interval).is_zero(), "Ranking not due at block");
+		ensure!(at > Self::ranking_computed_at(), "Ranking is stale");
+		ensure!(at <= <system::Module<T>>::block_number(), "Ranking is from the future");
+		ensure!(ranking.len() <= MAX_RANKING_LENGTH, "Ranking too long");
+
+		let mut previous: Option<(u32, T::KittyIndex)> = None;
+		for kitty_id in ranking {
+			let rarity = Self::kitties(kitty_id).ok_or("Invalid kitty_id")?.rarity();
+			if let Some((previous_rarity, previous_id)) = previous {
+				ensure!(
+					rarity < previous_rarity || (rarity == previous_rarity && *kitty_id > previous_id),
+					"Ranking out of order"
+				);
+			}
+			previous = Some((rarity, *kitty_id));
+		}
+
+		Ok(())
+	}
+
+	/// Get the kitties owned by an account, in the order of the owner's kitty list.
+	///
+	/// The walk stops after `OwnedKittiesCount` kitties, so a corrupted list can't loop.
+	pub fn kitties_of(owner: &T::AccountId) -> Vec<T::KittyIndex> {
+		let count = Self::owned_kitties_count(owner);
+		let mut kitties = Vec::new();
+		let mut index = T::KittyIndex::zero();
+		let mut next = Self::owned_kitties((owner.clone(), None)).and_then(|head| head.next);
+		while let Some(kitty_id) = next.filter(|_| index < count) {
+			kitties.push(kitty_id);
+			next = Self::owned_kitties((owner.clone(), Some(kitty_id))).and_then(|item| item.next);
+			index = index + One::one();
+		}
+		kitties
+	}
+
+	/// Get the age of a kitty in blocks as of the current block, `None` if it doesn't exist.
+	pub fn kitty_age(kitty_id: T::KittyIndex) -> Option<T::BlockNumber> {
+		if !<Kitties<T>>::exists(kitty_id) {
+			return None;
+		}
+		Some(<system::Module<T>>::block_number().saturating_sub(Self::birth_block_of(kitty_id)))
+	}
+
+	/// Get the species of a kitty, `None` if it doesn't exist.
+	pub fn species_of(kitty_id: T::KittyIndex) -> Option<Species> {
+		Self::kitties(kitty_id).map(|kitty| kitty.species())
+	}
+
+	/// Run the checks of `create` for `who` without creating anything.
+	///
+	/// Returns the id the kitty would get, or the error `create` would fail with.
+	pub fn dry_run_create(who: &T::AccountId) -> result::Result<T::KittyIndex, &'static str> {
+		Self::ensure_can_create(who)?;
+		Self::next_kitty_id()
+	}
+
+	/// Get the facts about a kitty that never change, `None` if it doesn't exist.
+	pub fn birth_certificate(
+		kitty_id: T::KittyIndex,
+	) -> Option<BirthCertificate<T::AccountId, T::KittyIndex, T::BlockNumber>> {
+		const HEX: &[u8; 16] = b"0123456789abcdef";
+		let kitty = Self::kitties(kitty_id)?;
+		let mut dna_hex = Vec::with_capacity(kitty.0.len() * 2);
+		for byte in kitty.0.iter() {
+			dna_hex.push(HEX[(byte >> 4) as usize]);
+			dna_hex.push(HEX[(byte & 0xf) as usize]);
+		}
+		Some(BirthCertificate {
+			dna_hex,
+			generation: Self::generation_of(kitty_id),
+			parents: Self::parents_of(kitty_id),
+			birth_block: Self::birth_block_of(kitty_id),
+			creator: Self::creator_of(kitty_id),
+		})
+	}
+
+	/// Get a kitty with its owner, listing and remaining cooldown, `None` if it doesn't exist.
+	pub fn kitty_profile(kitty_id: T::KittyIndex) -> Option<KittyProfile<T::AccountId, BalanceOf<T>>> {
+		let kitty = Self::kitties(kitty_id)?;
+		let now = <system::Module<T>>::block_number();
+		let cooldown_remaining = if Self::has_cooldown(kitty_id) {
+			Self::cooldown_end_of(kitty_id).saturating_sub(now).unique_saturated_into()
+		} else {
+			0
+		};
+		Some(KittyProfile {
+			kitty,
+			owner: Self::kitty_owner(kitty_id),
+			generation: Self::generation_of(kitty_id),
+			name: Self::name_of(kitty_id),
+			price: Self::kitty_price(kitty_id),
+			cooldown_remaining,
+			license: Self::license_of(kitty_id),
+		})
+	}
+
+	/// Get the kitties of an account that are off cooldown and neither retired nor locked.
+	pub fn ready_to_breed(account: &T::AccountId) -> Vec<T::KittyIndex> {
+		let now = <system::Module<T>>::block_number();
+		Self::kitties_of(account).into_iter()
+			.filter(|&kitty_id| !Self::is_retired(kitty_id) && !Self::is_locked(kitty_id))
+			.filter(|&kitty_id| !Self::has_cooldown(kitty_id) || Self::cooldown_end_of(kitty_id) <= now)
+			.collect()
+	}
+
+	/// Summarize the kitties owned and listed by an account.
+	pub fn account_summary(account: &T::AccountId) -> AccountSummary<T::KittyIndex, BalanceOf<T>> {
+		let owned = Self::kitties_of(account);
+		let listed: Vec<_> = owned.iter()
+			.filter_map(|&kitty_id| Self::kitty_price(kitty_id).map(|price| (kitty_id, price)))
+			.collect();
+		let total_listed_value = listed.iter()
+			.fold(Zero::zero(), |total: BalanceOf<T>, &(_, price)| total.saturating_add(price));
+
+		AccountSummary {
+			total_owned: owned.len() as u32,
+			created_count: Self::created_count(account),
+			owned,
+			listed,
+			total_listed_value,
+		}
+	}
+
+	/// Draw a random value for `sender`, values drawn for different subjects are independent.
+	fn random_value(subject: &[u8], sender: &T::AccountId) -> [u8; 16] {
+		if T::DeterministicGenetics::get() {
+			return (Self::kitties_count(), sender).using_encoded(blake2_128);
+		}
+
+		// The extrinsic index is missing for inherents and shared by batched calls, the nonce
+		// keeps values apart however many draws happen within a block
+		let nonce = Self::nonce();
+		<Nonce>::put(nonce.wrapping_add(1));
+		let seed = <system::Module<T>>::random_seed();
+		let now = <system::Module<T>>::block_number();
+		if seed == Default::default() {
+			// There is no randomness before the first blocks are built, stay varied at least
+			Self::deposit_event(RawEvent::InsecureRandomness(now));
+			return (subject, now, nonce, sender).using_encoded(blake2_128);
+		}
+		(subject, seed, sender, nonce, now).using_encoded(blake2_128)
+	}
+
+	/// The index after `current`, failing instead of overflowing.
+	fn next_index(current: T::KittyIndex) -> result::Result<T::KittyIndex, &'static str> {
+		current.checked_add(&One::one()).ok_or("Kitty index overflow")
+	}
+
+	fn next_kitty_id() -> result::Result<T::KittyIndex, &'static str> {
+		let kitty_id = Self::kitties_count();
+		Self::next_index(kitty_id)?;
+		Ok(kitty_id)
+	}
+
+	fn insert_owned_kitty(owner: &T::AccountId, kitty_id: T::KittyIndex) -> Result {
+		let next = Self::next_index(Self::owned_kitties_count(owner))?;
+		Self::owned_list_append(owner, kitty_id);
+		<OwnedKittiesCount<T>>::insert(owner, next);
+		<KittyOwners<T>>::insert(kitty_id, owner.clone());
+		Ok(())
+	}
+
+	/// Append a kitty to the end of the owned kitties list of an account.
+	fn owned_list_append(owner: &T::AccountId, kitty_id: T::KittyIndex) {
+		let mut head = Self::owned_kitties((owner.clone(), None)).unwrap_or_default();
+		let item = KittyLinkedItem { prev: head.prev, next: None };
+		match head.prev {
+			Some(last) => <OwnedKitties<T>>::mutate((owner.clone(), Some(last)), |last| {
+				if let Some(last) = last {
+					last.next = Some(kitty_id);
+				}
+			}),
+			None => head.next = Some(kitty_id),
+		}
+		head.prev = Some(kitty_id);
+		<OwnedKitties<T>>::insert((owner.clone(), None), head);
+		<OwnedKitties<T>>::insert((owner.clone(), Some(kitty_id)), item);
+	}
+
+	/// Unlink a kitty from the owned kitties list of an account, joining its neighbours.
+	fn owned_list_remove(owner: &T::AccountId, kitty_id: T::KittyIndex) -> Result {
+		let item = Self::owned_kitties((owner.clone(), Some(kitty_id))).ok_or("Kitty not in owner list")?;
+		// The neighbour of the first or last kitty is the head, which is keyed by `None`
+		<OwnedKitties<T>>::mutate((owner.clone(), item.prev), |prev| {
+			if let Some(prev) = prev {
+				prev.next = item.next;
+			}
+		});
+		<OwnedKitties<T>>::mutate((owner.clone(), item.next), |next| {
+			if let Some(next) = next {
+				next.prev = item.prev;
+			}
+		});
+		<OwnedKitties<T>>::remove((owner.clone(), Some(kitty_id)));
+		Ok(())
+	}
+
+	/// Get the owned kitties count of an account minus one, failing if the account owns none.
+	///
+	/// Only reachable with corrupted storage, as the caller checked the account owns a kitty.
+	fn owned_count_minus_one(owner: &T::AccountId) -> result::Result<T::KittyIndex, &'static str> {
+		Self::owned_kitties_count(owner)
+			.checked_sub(&One::one())
+			.ok_or("Owned kitties count inconsistent")
+	}
+
+	fn remove_owned_kitty(owner: &T::AccountId, kitty_id: T::KittyIndex) -> Result {
+		let count = Self::owned_count_minus_one(owner)?;
+
+		Self::owned_list_remove(owner, kitty_id)?;
+		<OwnedKittiesCount<T>>::insert(owner, count);
+		<KittyOwners<T>>::remove(kitty_id);
+
+		Ok(())
+	}
+
+	fn remove_auction(kitty_id: T::KittyIndex, end_block: T::BlockNumber) {
+		<Auctions<T>>::remove(kitty_id);
+		<AuctionsEndingAt<T>>::mutate(end_block, |ending| ending.retain(|id| *id != kitty_id));
+		<KittyLocks<T>>::remove(kitty_id);
+	}
+
+	/// Cancel the auctions ending at `now` that received no bid, unlocking their kitties.
+	///
+	/// Auctions with bids are left to be settled by `close_auction`.
+	fn sweep_expired_auctions(now: T::BlockNumber) {
+		for kitty_id in <AuctionsEndingAt<T>>::take(now) {
+			if let Some(auction) = Self::auction_of(kitty_id) {
+				if auction.highest_bid.is_none() {
+					Self::remove_auction(kitty_id, now);
+					Self::deposit_event(RawEvent::AuctionCancelled(kitty_id));
+				}
+			}
+		}
+	}
+
+	fn ensure_can_transfer(from: &T::AccountId, to: &T::AccountId, kitty_id: T::KittyIndex) -> Result {
+		ensure!(Self::kitty_owner(kitty_id).as_ref() == Some(from), "Not owner of kitty");
+		ensure!(!Self::is_locked(kitty_id), "Kitty is locked");
+		ensure!(<system::Module<T>>::block_number() >= Self::time_locked_until(kitty_id), "Kitty is time locked");
+
+		Self::ensure_can_receive(to, kitty_id)
+	}
+
+	/// Check that `to` can take ownership of a kitty, including its following deposit.
+	fn ensure_can_receive(to: &T::AccountId, kitty_id: T::KittyIndex) -> Result {
+		Self::owned_kitties_count(to)
+			.checked_add(&One::one())
+			.ok_or("Owned kitties count overflow")?;
+		ensure!(T::Currency::can_reserve(to, Self::following_deposit(to, kitty_id)), "Cannot reserve kitty deposit");
+
+		Ok(())
+	}
+
+	/// The creation deposit `to` has to reserve to receive a kitty, see `DepositFollowsKitty`.
+	fn following_deposit(to: &T::AccountId, kitty_id: T::KittyIndex) -> BalanceOf<T> {
+		match Self::deposit_of(kitty_id) {
+			Some((holder, deposit)) if T::DepositFollowsKitty::get() && holder != *to => deposit,
+			_ => Zero::zero(),
+		}
+	}
+
+	/// Check that `who` can create a kitty right now, returning its presale allowance left after.
+	fn ensure_can_create(who: &T::AccountId) -> result::Result<Option<u32>, &'static str> {
+		Self::ensure_not_paused(PAUSE_MINT)?;
+
+		ensure!(T::Currency::free_balance(who) >= T::MinCreatorBalance::get(), "Balance too low to create kitty");
+		let allowance = Self::presale_allowance(who, 1)?;
+		// Incubating kitties are unowned and don't take a deposit
+		if !T::Incubation::get() {
+			ensure!(Self::owned_kitties_count(who) < T::MaxKittiesPerAccount::get(), "Too many kitties owned");
+			Self::ensure_can_reserve_deposits(who, 1)?;
+		}
+
+		Ok(allowance)
+	}
+
+	/// Remove the egg of `who`, returning the deposit reserved when it was committed.
+	fn remove_egg(who: &T::AccountId) {
+		if let Some((_, _, deposit)) = <Eggs<T>>::take(who) {
+			T::Currency::unreserve(who, deposit);
+			<TotalEggDeposits<T>>::mutate(|total| *total = total.saturating_sub(deposit));
+		}
+	}
+
+	/// The last block an egg committed at `committed` can be revealed at.
+	fn egg_expiry(committed: T::BlockNumber) -> T::BlockNumber {
+		committed + T::EggRevealDelay::get() + T::EggRevealWindow::get()
+	}
+
+	/// The presale allowance `who` has left after creating `count` kitties.
+	///
+	/// `None` once the presale is over, fails if `who` is allowed fewer kitties.
+	fn presale_allowance(who: &T::AccountId, count: u32) -> result::Result<Option<u32>, &'static str> {
+		if <system::Module<T>>::block_number() >= T::PresaleEnd::get() {
+			return Ok(None);
+		}
+		Self::allowance(who).checked_sub(count).map(Some).ok_or("Not allowlisted for presale")
+	}
+
+	/// Check that `who` can reserve the creation deposits of `count` new kitties.
+	fn ensure_can_reserve_deposits(who: &T::AccountId, count: u32) -> Result {
+		let deposits = T::CreationDeposit::get().saturating_mul(count.into());
+		ensure!(T::Currency::can_reserve(who, deposits), "Cannot reserve creation deposit");
+		Ok(())
+	}
+
+	/// Run the breeds scheduled for `now`, dropping those that fail.
+	fn run_scheduled_breeds(now: T::BlockNumber) {
+		for (sender, kitty_id_1, kitty_id_2) in <ScheduledBreeds<T>>::take(now) {
+			let bred = Self::ensure_not_paused(PAUSE_BREED)
+				.and_then(|_| Self::do_breed(&sender, kitty_id_1, kitty_id_2, None).map(|_| ()));
+			if bred.is_err() {
+				Self::deposit_event(RawEvent::ScheduledBreedFailed(sender, kitty_id_1, kitty_id_2));
+			}
+		}
+	}
+
+	/// Move a kitty to a new owner, emitting `Transferred` only if `announce` is set.
+	fn do_transfer(from: &T::AccountId, to: &T::AccountId, kitty_id: T::KittyIndex, announce: bool) -> Result {
+		Self::ensure_can_transfer(from, to, kitty_id)?;
+		Self::move_kitty(from, to, kitty_id, announce)
+	}
+
+	/// Move a kitty without the transfer checks, which the caller must have run.
+	fn move_kitty(from: &T::AccountId, to: &T::AccountId, kitty_id: T::KittyIndex, announce: bool) -> Result {
+		let deposit = Self::following_deposit(to, kitty_id);
+		if !deposit.is_zero() {
+			T::Currency::reserve(to, deposit).map_err(|_| "Cannot reserve kitty deposit")?;
+			if let Some((holder, _)) = Self::deposit_of(kitty_id) {
+				T::Currency::unreserve(&holder, deposit);
+			}
+			<KittyDeposits<T>>::insert(kitty_id, (to.clone(), deposit));
+		}
+
+		Self::remove_owned_kitty(from, kitty_id)?;
+		Self::insert_owned_kitty(to, kitty_id)?;
+		if announce {
+			Self::deposit_event(RawEvent::Transferred(from.clone(), to.clone(), kitty_id));
+		}
+
+		// Approvals and listings are not carried over to the new owner
+		<KittyApprovals<T>>::remove(kitty_id);
+		Self::clear_listings(kitty_id);
+		if T::ResetCooldownOnTransfer::get() {
+			<KittyCooldownEnd<T>>::remove(kitty_id);
+		}
+
+		Ok(())
+	}
+
+	/// Remove the name of a kitty, freeing it for other kitties.
+	fn clear_name(kitty_id: T::KittyIndex) {
+		let name = <KittyNames<T>>::take(kitty_id);
+		if Self::name_owner(&name) == Some(kitty_id) {
+			<NameOwner<T>>::remove(&name);
+		}
+	}
+
+	/// Remove the sale price and sire offer of a kitty.
+	fn clear_listings(kitty_id: T::KittyIndex) {
+		if <KittyPrices<T>>::exists(kitty_id) {
+			<KittyPrices<T>>::remove(kitty_id);
+			Self::deposit_event(RawEvent::Unlisted(kitty_id));
+		}
+		<SireOffers<T>>::remove(kitty_id);
+	}
+
+	/// Drop a sire from the wishlists of the accounts wishing for it.
+	fn clear_wishers(sire: T::KittyIndex) {
+		for wisher in <SireWishers<T>>::take(sire) {
+			<BreedWishlist<T>>::mutate(&wisher, |wishlist| wishlist.retain(|id| *id != sire));
+		}
+	}
+
+	/// Tell the accounts wishing for a sire that it is now offered.
+	fn notify_wishers(sire: T::KittyIndex) {
+		let wishers = Self::sire_wishers(sire);
+		if !wishers.is_empty() {
+			Self::deposit_event(RawEvent::SireAvailable(sire, wishers));
+		}
+	}
+
+	/// Mint a kitty with a random DNA, emitting `CreatedWithDna` only if `announce` is set.
+	fn mint_kitty(owner: &T::AccountId, announce: bool) -> result::Result<T::KittyIndex, &'static str> {
+		let kitty_id = Self::next_kitty_id()?;
+
+		// Generate a random 128bit value
+		let dna = Self::random_value(CREATE_SUBJECT, owner);
+		ensure!(!<DnaExists<T>>::exists(&dna), "Kitty DNA already exists");
+
+		let deposit = T::CreationDeposit::get();
+		if !deposit.is_zero() {
+			T::Currency::reserve(owner, deposit).map_err(|_| "Cannot reserve creation deposit")?;
+			<KittyDeposits<T>>::insert(kitty_id, (owner.clone(), deposit));
+		}
+
+		// Create and store kitty
+		Self::insert_kitty(owner, kitty_id, Kitty(dna), 0)?;
+		if announce {
+			Self::deposit_event(RawEvent::CreatedWithDna(owner.clone(), kitty_id, dna));
+		}
+
+		Ok(kitty_id)
+	}
+
+	/// Mint a kitty with a random DNA into the incubator, without an owner.
+	fn incubate_kitty(creator: &T::AccountId) -> result::Result<T::KittyIndex, &'static str> {
+		let kitty_id = Self::next_kitty_id()?;
+
+		let dna = Self::random_value(CREATE_SUBJECT, creator);
+		ensure!(!<DnaExists<T>>::exists(&dna), "Kitty DNA already exists");
+
+		Self::store_kitty(creator, kitty_id, Kitty(dna), 0)?;
+		<Incubating<T>>::insert(kitty_id, true);
+		<CreatedKittiesCount<T>>::mutate(creator, |count| *count = count.saturating_add(1));
+		Self::deposit_event(RawEvent::Incubated(creator.clone(), kitty_id, dna));
+
+		Ok(kitty_id)
+	}
+
+	/// The transfer window and count of `from` after `transfers` more transfers out.
+	///
+	/// Fails if this takes `from` over `MaxTransfersPerWindow` kitties in its current window.
+	fn next_transfer_count(from: &T::AccountId, transfers: u32) -> result::Result<(T::BlockNumber, u32), &'static str> {
+		let now = <system::Module<T>>::block_number();
+		let (start, count) = Self::transfer_count(from);
+		let (start, count) = if now >= start + T::TransferWindow::get() { (now, 0) } else { (start, count) };
+		let count = count.checked_add(transfers)
+			.filter(|count| *count <= T::MaxTransfersPerWindow::get())
+			.ok_or("Transfer rate limited")?;
+		Ok((start, count))
+	}
+
+	/// Fail if any of the given `PAUSE_*` operations is paused.
+	fn ensure_not_paused(ops: u8) -> Result {
+		ensure!(Self::paused_ops() & ops == 0, "Operation is paused");
+		Ok(())
+	}
+
+	/// Fail if a batch call has more than `MaxBatchSize` items.
+	fn ensure_batch_size(len: usize) -> Result {
+		ensure!(len <= T::MaxBatchSize::get() as usize, "Batch too large");
+		Ok(())
+	}
+
+	/// Transfer a kitty on behalf of its owner, counting it against the owner's transfer rate.
+	fn do_transfer_out(from: &T::AccountId, to: &T::AccountId, kitty_id: T::KittyIndex, announce: bool) -> Result {
+		let transfer_count = Self::next_transfer_count(from, 1)?;
+		Self::do_transfer(from, to, kitty_id, announce)?;
+		<TransferCount<T>>::insert(from, transfer_count);
+
+		Ok(())
+	}
+
+	/// Destroy a kitty owned by `owner`, returning its creation deposit.
+	///
+	/// With `UseTombstones` the kitty is only disowned and moved to the graveyard.
+	fn remove_kitty(owner: &T::AccountId, kitty_id: T::KittyIndex, kitty: &Kitty) -> Result {
+		Self::remove_owned_kitty(owner, kitty_id)?;
+		let generation = Self::generation_of(kitty_id);
+		<KittiesByGeneration<T>>::mutate(generation, |kitties| kitties.retain(|id| *id != kitty_id));
+		if T::UseTombstones::get() {
+			<Graveyard<T>>::insert(kitty_id, true);
+		} else {
+			<Kitties<T>>::remove(kitty_id);
+			Self::remove_dna_index(kitty_id, &kitty.0);
+			<KittyGeneration<T>>::remove(kitty_id);
+			<KittyBirthBlock<T>>::remove(kitty_id);
+			<KittyCreators<T>>::remove(kitty_id);
+			<KittyNameHistory<T>>::remove(kitty_id);
+		}
+		<KittyApprovals<T>>::remove(kitty_id);
+		<KittyCooldownEnd<T>>::remove(kitty_id);
+		<KittyLicense<T>>::remove(kitty_id);
+		<KittyLevel<T>>::remove(kitty_id);
+		<KittyTimeLocks<T>>::remove(kitty_id);
+		<RetiredKitties<T>>::remove(kitty_id);
+		<GenderCounts>::mutate(|(males, females)| match kitty.gender() {
+			Gender::Male => *males = males.saturating_sub(1),
+			Gender::Female => *females = females.saturating_sub(1),
+		});
+		Self::clear_listings(kitty_id);
+		Self::clear_wishers(kitty_id);
+		Self::clear_name(kitty_id);
+		if let Some((holder, deposit)) = <KittyDeposits<T>>::take(kitty_id) {
+			T::Currency::unreserve(&holder, deposit);
+		}
+
+		Ok(())
+	}
+
+	/// Set the generation of a kitty, moving it between the `KittiesByGeneration` buckets.
+	///
+	/// Changes to the generation of an existing kitty are announced with `GenerationChanged`.
+	fn set_generation(kitty_id: T::KittyIndex, generation: u16) {
+		let old = if <KittyGeneration<T>>::exists(kitty_id) { Some(Self::generation_of(kitty_id)) } else { None };
+		if old == Some(generation) {
+			return;
+		}
+
+		if let Some(old) = old {
+			<KittiesByGeneration<T>>::mutate(old, |kitties| kitties.retain(|id| *id != kitty_id));
+		}
+		<KittyGeneration<T>>::insert(kitty_id, generation);
+		<KittiesByGeneration<T>>::mutate(generation, |kitties| kitties.push(kitty_id));
+		if let Some(old) = old {
+			Self::deposit_event(RawEvent::GenerationChanged(kitty_id, old, generation));
+		}
+	}
+
+	/// Record the parents of a new kitty, and the kitty as a child of each of them.
+	fn set_parents(kitty_id: T::KittyIndex, parents: (T::KittyIndex, T::KittyIndex)) {
+		<KittyParents<T>>::insert(kitty_id, parents);
+		<KittyChildren<T>>::mutate(parents.0, |children| children.push(kitty_id));
+		if parents.1 != parents.0 {
+			<KittyChildren<T>>::mutate(parents.1, |children| children.push(kitty_id));
+		}
+	}
+
+	/// Remove the DNA index entry of a kitty, unless it belongs to an older kitty with the same DNA.
+	fn remove_dna_index(kitty_id: T::KittyIndex, dna: &[u8; 16]) {
+		if Self::kitties_id_by_dna(dna) == Some(kitty_id) {
+			<DnaExists<T>>::remove(dna);
+		}
+	}
+
+	/// Build `DnaExists` from the existing kitties and record the ones with a duplicate DNA.
+	///
+	/// The lowest id keeps the DNA, duplicates are left to governance rather than burned. Each
+	/// call scans up to `MAX_DNA_MIGRATION_PER_BLOCK` kitties from `DnaIndexCursor`.
+	fn migrate_dna_index() {
+		let mut duplicates = Self::duplicate_dna_kitties();
+		let count = Self::kitties_count();
+		let mut kitty_id = Self::dna_index_cursor();
+		let mut scanned = 0;
+		while kitty_id < count && scanned < MAX_DNA_MIGRATION_PER_BLOCK {
+			if let Some(kitty) = Self::kitties(kitty_id) {
+				let original = Self::kitties_id_by_dna(kitty.0).filter(|original| {
+					*original < kitty_id && Self::kitties(*original).as_ref() == Some(&kitty)
+				});
+				if original.is_some() {
+					if !duplicates.contains(&kitty_id) {
+						duplicates.push(kitty_id);
+					}
+				} else {
+					<DnaExists<T>>::insert(kitty.0, kitty_id);
+				}
+			}
+			kitty_id = kitty_id + One::one();
+			scanned += 1;
+		}
+		<DuplicateDnaKitties<T>>::put(duplicates);
+		if kitty_id < count {
+			<DnaIndexCursor<T>>::put(kitty_id);
+		} else {
+			<DnaIndexCursor<T>>::kill();
+			<DnaIndexMigrated>::put(true);
+		}
+	}
+
+	fn insert_kitty(owner: &T::AccountId, kitty_id: T::KittyIndex, kitty: Kitty, generation: u16) -> Result {
+		// Store the ownership information first, as only the owned count can still overflow
+		// once `kitty_id` was taken from `next_kitty_id`
+		Self::insert_owned_kitty(owner, kitty_id)?;
+		Self::store_kitty(owner, kitty_id, kitty, generation)?;
+		<CreatedKittiesCount<T>>::mutate(owner, |count| *count = count.saturating_add(1));
+		Ok(())
+	}
+
+	/// Store a new kitty made by `creator`, without any ownership information.
+	fn store_kitty(creator: &T::AccountId, kitty_id: T::KittyIndex, kitty: Kitty, generation: u16) -> Result {
+		let next = Self::next_index(kitty_id)?;
+		<DnaExists<T>>::insert(kitty.0, kitty_id);
+		<KittyCreators<T>>::insert(kitty_id, creator);
+		<Kitties<T>>::insert(kitty_id, kitty);
+		<KittiesCount<T>>::put(next);
+		<KittyBirthBlock<T>>::insert(kitty_id, <system::Module<T>>::block_number());
+		Self::set_generation(kitty_id, generation);
+		<GenderCounts>::mutate(|(males, females)| match kitty.gender() {
+			Gender::Male => *males = males.saturating_add(1),
+			Gender::Female => *females = females.saturating_add(1),
+		});
+		Ok(())
+	}
+
+	fn do_breed(
+		sender: &T::AccountId,
+		kitty_id_1: T::KittyIndex,
+		kitty_id_2: T::KittyIndex,
+		donor: Option<T::KittyIndex>,
+	) -> result::Result<T::KittyIndex, &'static str> {
+		let (kitty1, kitty2, generation) = Self::ensure_can_breed(sender, kitty_id_1, kitty_id_2)?;
+
+		let now = <system::Module<T>>::block_number();
+		let cooldown_1 = Self::has_cooldown(kitty_id_1);
+		let cooldown_2 = Self::has_cooldown(kitty_id_2);
+
+		// The donor follows the same rules as the parents but doesn't incur a cooldown
+		let donor_dna = match donor {
+			Some(donor) => {
+				let donor_kitty = Self::kitties(donor).ok_or("Invalid donor")?;
+				ensure!(donor != kitty_id_1 && donor != kitty_id_2, "Donor must not be a parent");
+				ensure!(Self::kitty_owner(donor).as_ref() == Some(sender), "Not owner of donor");
+				ensure!(!Self::has_cooldown(donor) || Self::cooldown_end_of(donor) <= now, "Donor is on breeding cooldown");
+				Some(donor_kitty.0)
+			},
+			None => None,
+		};
+
+		ensure!(Self::owned_kitties_count(sender) < T::MaxKittiesPerAccount::get(), "Too many kitties owned");
+		let kitty_id = Self::next_kitty_id()?;
+		// The remaining check of `insert_kitty`, which can't fail once the fee is charged
+		Self::next_index(Self::owned_kitties_count(sender))?;
+
+		let kitty1_dna = kitty1.0;
+		let kitty2_dna = kitty2.0;
+
+		// Generate a random 128bit value
+		let mut selector = parent_selector(
+			Self::random_value(BREED_SUBJECT, sender),
+			(kitty_id_1, &kitty1_dna),
+			(kitty_id_2, &kitty2_dna),
+		);
+		if T::RarityBiasedBreeding::get() {
+			selector = biased_selector(kitty1.rarity(), kitty2.rarity(), selector);
+		}
+		let mut new_dna = [0u8; 16];
+
+		// Combine parents and selector to create new kitty
+		for i in 0..kitty1_dna.len() {
+			new_dna[i] = combine_dna(kitty1_dna[i], kitty2_dna[i], selector[i]);
+		}
+
+		// The gender bit is seeded from the parents and selector rather than the combined DNA
+		let gender = if T::BalancedGenders::get() {
+			balanced_gender(Self::gender_counts(), selector[0])
+		} else {
+			offspring_gender(&kitty1_dna, &kitty2_dna, &selector)
+		};
+		new_dna[0] = (new_dna[0] & !1) | gender as u8;
+
+		if let Some(donor_dna) = donor_dna {
+			let count = T::DonorGeneCount::get().min(15) as usize;
+			let start = new_dna.len() - count;
+			new_dna[start..].copy_from_slice(&donor_dna[start..]);
+		}
+
+		let mask = T::LockedTraitMask::get();
+		for i in 0..new_dna.len() {
+			new_dna[i] = combine_dna(kitty1_dna[i], new_dna[i], mask[i]);
+		}
+
+		ensure!(!<DnaExists<T>>::exists(&new_dna), "Kitty DNA already exists");
+
+		let fee = Self::breeding_fee(generation - 1);
+		if !fee.is_zero() {
+			T::Currency::transfer(sender, &T::Treasury::get(), fee).map_err(|_| "Cannot pay breeding fee")?;
+		}
+
+		Self::insert_kitty(sender, kitty_id, Kitty(new_dna), generation)?;
+		Self::deposit_event(RawEvent::CreatedWithDna(sender.clone(), kitty_id, new_dna));
+		Self::set_parents(kitty_id, (kitty_id_1, kitty_id_2));
+
+		let cooldown_end = now + T::BreedCooldown::get();
+		if cooldown_1 {
+			<KittyCooldownEnd<T>>::insert(kitty_id_1, cooldown_end);
+		}
+		if cooldown_2 {
+			<KittyCooldownEnd<T>>::insert(kitty_id_2, cooldown_end);
+		}
+		let (low, high) = pair_key(kitty_id_1, kitty_id_2);
+		<PairOffspringCount<T>>::mutate(&low, &high, |count| *count = count.saturating_add(1));
+
+		<BreedingTokens<T>>::mutate(sender, |tokens| *tokens = tokens.saturating_add(T::BreedingTokenReward::get()));
+
+		// Anyone can rebuild the DNA by combining the parents with the selector and applying the flips
+		let mut flips = [0u8; 16];
+		for i in 0..new_dna.len() {
+			flips[i] = new_dna[i] ^ combine_dna(kitty1_dna[i], kitty2_dna[i], selector[i]);
+		}
+		Self::deposit_event(RawEvent::Bred(sender.clone(), kitty_id_1, kitty_id_2, kitty_id, selector, flips));
+
+		Ok(kitty_id)
+	}
+
+	/// The fee to breed parents whose highest generation is `generation`.
+	pub fn breeding_fee(generation: u16) -> BalanceOf<T> {
+		if generation == 0 && T::FreeGen0Breed::get() {
+			return Zero::zero();
+		}
+		let multiplier: BalanceOf<T> = T::BreedingFeeCurve::convert(generation).into();
+		Self::breed_fee().unwrap_or_else(T::BaseBreedingFee::get).saturating_mul(multiplier)
+	}
+
+	/// Check that `sender` can breed the given parents right now.
+	///
+	/// Returns the parents and the generation of their offspring, the error converts into the dispatch error.
+	fn ensure_can_breed(
+		sender: &T::AccountId,
+		kitty_id_1: T::KittyIndex,
+		kitty_id_2: T::KittyIndex,
+	) -> result::Result<(Kitty, Kitty, u16), Incompatibility> {
+		let kitty1 = Self::kitties(kitty_id_1).ok_or(Incompatibility::InvalidKitty)?;
+		let kitty2 = Self::kitties(kitty_id_2).ok_or(Incompatibility::InvalidKitty)?;
+
+		ensure!(kitty_id_1 != kitty_id_2, Incompatibility::SameKitty);
+		ensure!(Self::kitty_owner(kitty_id_1).as_ref() == Some(sender), Incompatibility::DifferentOwners);
+		ensure!(Self::kitty_owner(kitty_id_2).as_ref() == Some(sender), Incompatibility::DifferentOwners);
+		ensure!(!Self::is_retired(kitty_id_1) && !Self::is_retired(kitty_id_2), Incompatibility::Retired);
+		ensure!(!T::SameSpeciesOnly::get() || kitty1.species() == kitty2.species(), Incompatibility::SpeciesMismatch);
+		let (low, high) = pair_key(kitty_id_1, kitty_id_2);
+		ensure!(Self::pair_offspring_count(&low, &high) < T::MaxPairOffspring::get(), Incompatibility::PairLimit);
+
+		let generation_1 = Self::generation_of(kitty_id_1);
+		let generation_2 = Self::generation_of(kitty_id_2);
+		let generation_gap = generation_1.max(generation_2) - generation_1.min(generation_2);
+		ensure!(generation_gap <= T::MaxGenerationGap::get(), Incompatibility::GenerationGap);
+		let generation = generation_1.max(generation_2).checked_add(1)
+			.filter(|generation| *generation <= T::MaxGeneration::get())
+			.ok_or(Incompatibility::MaxGeneration)?;
+
+		let now = <system::Module<T>>::block_number();
+		ensure!(!Self::has_cooldown(kitty_id_1) || Self::cooldown_end_of(kitty_id_1) <= now, Incompatibility::OnCooldown);
+		ensure!(!Self::has_cooldown(kitty_id_2) || Self::cooldown_end_of(kitty_id_2) <= now, Incompatibility::OnCooldown);
+
+		Ok((kitty1, kitty2, generation))
+	}
+
+	/// Check the invariants of the kitties storage, failing on the first broken one.
+	///
+	/// This reads every kitty and is meant to be run against live state before upgrades.
+	#[cfg(any(feature = "try-runtime", test))]
+	pub fn try_state() -> Result {
+		let count = Self::kitties_count();
+		let mut owners: Vec<T::AccountId> = Vec::new();
+		let mut existing = T::KittyIndex::zero();
+		let mut kitty_id = T::KittyIndex::zero();
+		while kitty_id < count {
+			match Self::kitties(kitty_id) {
+				Some(kitty) => {
+					ensure!(
+						Self::kitties_id_by_dna(kitty.0) == Some(kitty_id) || Self::duplicate_dna_kitties().contains(&kitty_id),
+						"DnaExists doesn't match kitty"
+					);
+					if Self::is_incubating(kitty_id) || Self::is_tombstoned(kitty_id) {
+						ensure!(Self::kitty_owner(kitty_id).is_none(), "Ownerless kitty has an owner");
+					} else {
+						let owner = Self::kitty_owner(kitty_id).ok_or("Kitty has no owner")?;
+						ensure!(
+							<OwnedKitties<T>>::exists((owner.clone(), Some(kitty_id))),
+							"Kitty not in owner list"
+						);
+						if !owners.contains(&owner) {
+							owners.push(owner);
+						}
+						existing = existing + One::one();
+					}
+				},
+				None => {
+					ensure!(Self::kitty_owner(kitty_id).is_none(), "Missing kitty has an owner");
+					ensure!(Self::kitty_price(kitty_id).is_none(), "Missing kitty is listed");
+				},
+			}
+			kitty_id = kitty_id + One::one();
+		}
+
+		let owned = owners.iter()
+			.fold(T::KittyIndex::zero(), |total, owner| total + Self::owned_kitties_count(owner));
+		ensure!(owned == existing, "Owned kitties counts don't match kitties");
+
+		Ok(())
+	}
+
+	/// Whether breeding sets and checks the cooldown of the given parent.
+	fn has_cooldown(kitty_id: T::KittyIndex) -> bool {
+		!(T::Gen0NoCooldown::get() && Self::generation_of(kitty_id) == 0)
+	}
+}
+
+impl<T: Trait> support::unsigned::ValidateUnsigned for Module<T> {
+	type Call = Call<T>;
+
+	/// Only the rarity ranking is accepted unsigned, once per ranking block.
+	fn validate_unsigned(call: &Self::Call) -> TransactionValidity {
+		match call {
+			Call::submit_rarity_ranking(ranking, at) => {
+				if Self::ensure_valid_ranking(ranking, *at).is_err() {
+					return InvalidTransaction::Stale.into();
+				}
+				Ok(ValidTransaction {
+					priority: 0,
+					requires: vec![],
+					provides: vec![(RARITY_RANKING_KEY, at).encode()],
+					longevity: TransactionLongevity::max_value(),
+					propagate: true,
+				})
+			},
+			_ => InvalidTransaction::Call.into(),
+		}
+	}
+}
+
+decl_runtime_apis! {
+	/// The API to query kitties state.
+	pub trait KittiesApi<AccountId, KittyIndex, Balance, BlockNumber> where
+		AccountId: Codec,
+		KittyIndex: Codec,
+		Balance: Codec,
+		BlockNumber: Codec,
+	{
+		/// Get the kitties with the given ids, in the same order and `None` for missing ids.
+		fn kitties_by_ids(ids: Vec<KittyIndex>) -> Vec<Option<Kitty>>;
+		/// Summarize the kitties owned and listed by an account.
+		fn account_summary(account: AccountId) -> AccountSummary<KittyIndex, Balance>;
+		/// Get the age of a kitty in blocks as of the current block, `None` if it doesn't exist.
+		fn kitties_age(kitty_id: KittyIndex) -> Option<BlockNumber>;
+		/// Get the kitty holding the given DNA, `None` if no kitty has it.
+		fn kitties_id_by_dna(dna: [u8; 16]) -> Option<KittyIndex>;
+		/// Get the owners of the given kitties, in the same order and `None` for missing ids.
+		fn owners_of(ids: Vec<KittyIndex>) -> Vec<Option<AccountId>>;
+		/// Page through all kitties with their owners, see `Module::kitties_snapshot`.
+		fn kitties_snapshot(start: KittyIndex, limit: u32) -> Vec<(KittyIndex, Kitty, AccountId)>;
+		/// Describe the kitty index type, its first id and bit width.
+		fn kitty_index_info() -> KittyIndexInfo<KittyIndex>;
+		/// Get the traits to render a kitty from, see `Kitty::render_traits`.
+		fn kitties_render_traits(kitty_id: KittyIndex) -> Option<Vec<u8>>;
+		/// Get the funds reserved by the kitties module.
+		fn total_reserved() -> Balance;
+		/// Page through the kitties listed for sale, see `Module::active_listings`.
+		fn active_listings(start: KittyIndex, limit: u32) -> Vec<(KittyIndex, AccountId, Balance)>;
+		/// Check whether two kitties can be bred, see `Module::breeding_compatibility`.
+		fn breeding_compatibility(kitty_id_1: KittyIndex, kitty_id_2: KittyIndex) -> CompatibilityReport<BlockNumber>;
+		/// Get the kitties of an account that can breed right now.
+		fn ready_to_breed(account: AccountId) -> Vec<KittyIndex>;
+		/// Get a kitty with its owner, listing and remaining cooldown.
+		fn kitty_profile(kitty_id: KittyIndex) -> Option<KittyProfile<AccountId, Balance>>;
+		/// Get the facts about a kitty that never change, see `Module::birth_certificate`.
+		fn birth_certificate(kitty_id: KittyIndex) -> Option<BirthCertificate<AccountId, KittyIndex, BlockNumber>>;
+		/// Check whether an account can create a kitty, see `Module::dry_run_create`.
+		///
+		/// The error is the message `create` would fail with.
+		fn dry_run_create(account: AccountId) -> result::Result<KittyIndex, Vec<u8>>;
+		/// Get the kitties sharing a parent with a kitty, see `Module::siblings_of`.
+		fn siblings_of(kitty_id: KittyIndex) -> Vec<KittyIndex>;
+		/// Get the cheapest listed kitty, see `Module::floor_price`.
+		fn floor_price(generation: Option<u16>) -> Option<(KittyIndex, Balance)>;
+		/// Get the kitties of an account with their prices, see `Module::owned_with_prices`.
+		fn owned_with_prices(account: AccountId) -> Vec<(KittyIndex, Option<Balance>)>;
+		/// Get the number of living male and female kitties.
+		fn gender_distribution() -> (u32, u32);
+		/// Appraise a kitty, see `Module::appraise`.
+		fn appraise(kitty_id: KittyIndex) -> Option<u64>;
+	}
+}
+
+/// tests for this module
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use std::cell::RefCell;
+	use runtime_io::with_externalities;
+	use primitives::{H256, Blake2Hasher, offchain::testing::TestOffchainExt};
+	use support::{impl_outer_origin, impl_outer_event, assert_ok, assert_noop, parameter_types, unsigned::ValidateUnsigned};
+	use sr_primitives::{traits::{BlakeTwo256, IdentityLookup, ConvertInto, OnInitialize}, testing::{Header, TestSignature}};
+	use sr_primitives::weights::Weight;
+	use sr_primitives::Perbill;
+
+	impl_outer_origin! {
+		pub enum Origin for Test {}
+	}
+
+	mod kitties {
+		pub use super::super::*;
+	}
+
+	impl_outer_event! {
+		pub enum TestEvent for Test {
+			balances<T>,
+			kitties<T>,
+		}
+	}
+
+	// For testing the module, we construct most of a mock runtime. This means
+	// first constructing a configuration type (`Test`) which `impl`s each of the
+	// configuration traits of modules we want to use.
+	#[derive(Clone, Eq, PartialEq)]
+	pub struct Test;
+	parameter_types! {
+		pub const BlockHashCount: u64 = 250;
+		pub const MaximumBlockWeight: Weight = 1024;
+		pub const MaximumBlockLength: u32 = 2 * 1024;
+		pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+	}
+	impl system::Trait for Test {
+		type Origin = Origin;
+		type Call = ();
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<Self::AccountId>;
+		type Header = Header;
+		type WeightMultiplierUpdate = ();
+		type Event = TestEvent;
+		type BlockHashCount = BlockHashCount;
+		type MaximumBlockWeight = MaximumBlockWeight;
+		type MaximumBlockLength = MaximumBlockLength;
+		type AvailableBlockRatio = AvailableBlockRatio;
+		type Version = ();
+	}
+	pub struct ExistentialDeposit;
+	impl Get<u64> for ExistentialDeposit {
+		fn get() -> u64 {
+			EXISTENTIAL_DEPOSIT.with(|v| *v.borrow())
+		}
+	}
+	parameter_types! {
+		pub const TransferFee: u64 = 0;
+		pub const CreationFee: u64 = 0;
+		pub const TransactionBaseFee: u64 = 0;
+		pub const TransactionByteFee: u64 = 0;
+	}
+	impl balances::Trait for Test {
+		type Balance = u64;
+		type OnFreeBalanceZero = ();
+		type OnNewAccount = ();
+		type Event = TestEvent;
+		type TransactionPayment = ();
+		type TransferPayment = ();
+		type DustRemoval = ();
+		type ExistentialDeposit = ExistentialDeposit;
+		type TransferFee = TransferFee;
+		type CreationFee = CreationFee;
+		type TransactionBaseFee = TransactionBaseFee;
+		type TransactionByteFee = TransactionByteFee;
+		type WeightToFee = ConvertInto;
+	}
+	thread_local! {
+		static EXISTENTIAL_DEPOSIT: RefCell<u64> = RefCell::new(0);
+		static GEN0_NO_COOLDOWN: RefCell<bool> = RefCell::new(false);
+		static DETERMINISTIC_GENETICS: RefCell<bool> = RefCell::new(false);
+		static MAX_GENERATION_GAP: RefCell<u16> = RefCell::new(u16::max_value());
+		static MAX_KITTIES_PER_ACCOUNT: RefCell<u32> = RefCell::new(u32::max_value());
+		static KITTY_TRANSFER_FEE: RefCell<u64> = RefCell::new(0);
+		static BASE_BREEDING_FEE: RefCell<u64> = RefCell::new(0);
+		static ANTI_SNIPE_WINDOW: RefCell<u64> = RefCell::new(0);
+		static MAX_TRANSFERS_PER_WINDOW: RefCell<u32> = RefCell::new(u32::max_value());
+		static RESET_COOLDOWN_ON_TRANSFER: RefCell<bool> = RefCell::new(false);
+		static UNIQUE_NAMES: RefCell<bool> = RefCell::new(false);
+		static BALANCED_GENDERS: RefCell<bool> = RefCell::new(false);
+		static INCUBATION: RefCell<bool> = RefCell::new(false);
+		static LOCKED_TRAIT_MASK: RefCell<[u8; 16]> = RefCell::new([0; 16]);
+		static CREATION_DEPOSIT: RefCell<u64> = RefCell::new(0);
+		static DEPOSIT_FOLLOWS_KITTY: RefCell<bool> = RefCell::new(false);
+		static PRESALE_END: RefCell<u64> = RefCell::new(0);
+		static SAME_SPECIES_ONLY: RefCell<bool> = RefCell::new(false);
+		static COMPACT_BATCH_EVENTS: RefCell<bool> = RefCell::new(false);
+		static USE_TOMBSTONES: RefCell<bool> = RefCell::new(false);
+		static FREE_GEN0_BREED: RefCell<bool> = RefCell::new(false);
+		static MAX_PAIR_OFFSPRING: RefCell<u32> = RefCell::new(u32::max_value());
+		static EGG_DEPOSIT: RefCell<u64> = RefCell::new(25);
+	}
+	pub struct Gen0NoCooldown;
+	impl Get<bool> for Gen0NoCooldown {
+		fn get() -> bool {
+			GEN0_NO_COOLDOWN.with(|v| *v.borrow())
+		}
+	}
+	pub struct DeterministicGenetics;
+	impl Get<bool> for DeterministicGenetics {
+		fn get() -> bool {
+			DETERMINISTIC_GENETICS.with(|v| *v.borrow())
+		}
+	}
+	pub struct MaxGenerationGap;
+	impl Get<u16> for MaxGenerationGap {
+		fn get() -> u16 {
+			MAX_GENERATION_GAP.with(|v| *v.borrow())
+		}
+	}
+	pub struct MaxKittiesPerAccount;
+	impl Get<u32> for MaxKittiesPerAccount {
+		fn get() -> u32 {
+			MAX_KITTIES_PER_ACCOUNT.with(|v| *v.borrow())
+		}
+	}
+	pub struct KittyTransferFee;
+	impl Get<u64> for KittyTransferFee {
+		fn get() -> u64 {
+			KITTY_TRANSFER_FEE.with(|v| *v.borrow())
+		}
+	}
+	pub struct BaseBreedingFee;
+	impl Get<u64> for BaseBreedingFee {
+		fn get() -> u64 {
+			BASE_BREEDING_FEE.with(|v| *v.borrow())
+		}
+	}
+	pub struct AntiSnipeWindow;
+	impl Get<u64> for AntiSnipeWindow {
+		fn get() -> u64 {
+			ANTI_SNIPE_WINDOW.with(|v| *v.borrow())
+		}
+	}
+	pub struct MaxTransfersPerWindow;
+	impl Get<u32> for MaxTransfersPerWindow {
+		fn get() -> u32 {
+			MAX_TRANSFERS_PER_WINDOW.with(|v| *v.borrow())
+		}
+	}
+	pub struct ResetCooldownOnTransfer;
+	impl Get<bool> for ResetCooldownOnTransfer {
+		fn get() -> bool {
+			RESET_COOLDOWN_ON_TRANSFER.with(|v| *v.borrow())
+		}
+	}
+	pub struct UniqueNames;
+	impl Get<bool> for UniqueNames {
+		fn get() -> bool {
+			UNIQUE_NAMES.with(|v| *v.borrow())
+		}
+	}
+	pub struct BalancedGenders;
+	impl Get<bool> for BalancedGenders {
+		fn get() -> bool {
+			BALANCED_GENDERS.with(|v| *v.borrow())
+		}
+	}
+	pub struct Incubation;
+	impl Get<bool> for Incubation {
+		fn get() -> bool {
+			INCUBATION.with(|v| *v.borrow())
+		}
+	}
+	pub struct LockedTraitMask;
+	impl Get<[u8; 16]> for LockedTraitMask {
+		fn get() -> [u8; 16] {
+			LOCKED_TRAIT_MASK.with(|v| *v.borrow())
+		}
+	}
+	pub struct CreationDeposit;
+	impl Get<u64> for CreationDeposit {
+		fn get() -> u64 {
+			CREATION_DEPOSIT.with(|v| *v.borrow())
+		}
+	}
+	pub struct DepositFollowsKitty;
+	impl Get<bool> for DepositFollowsKitty {
+		fn get() -> bool {
+			DEPOSIT_FOLLOWS_KITTY.with(|v| *v.borrow())
+		}
+	}
+	pub struct PresaleEnd;
+	impl Get<u64> for PresaleEnd {
+		fn get() -> u64 {
+			PRESALE_END.with(|v| *v.borrow())
+		}
+	}
+	pub struct SameSpeciesOnly;
+	impl Get<bool> for SameSpeciesOnly {
+		fn get() -> bool {
+			SAME_SPECIES_ONLY.with(|v| *v.borrow())
+		}
+	}
+	pub struct CompactBatchEvents;
+	impl Get<bool> for CompactBatchEvents {
+		fn get() -> bool {
+			COMPACT_BATCH_EVENTS.with(|v| *v.borrow())
+		}
+	}
+	pub struct UseTombstones;
+	impl Get<bool> for UseTombstones {
+		fn get() -> bool {
+			USE_TOMBSTONES.with(|v| *v.borrow())
+		}
+	}
+	pub struct FreeGen0Breed;
+	impl Get<bool> for FreeGen0Breed {
+		fn get() -> bool {
+			FREE_GEN0_BREED.with(|v| *v.borrow())
+		}
+	}
+	pub struct MaxPairOffspring;
+	impl Get<u32> for MaxPairOffspring {
+		fn get() -> u32 {
+			MAX_PAIR_OFFSPRING.with(|v| *v.borrow())
+		}
+	}
+	pub struct EggDeposit;
+	impl Get<u64> for EggDeposit {
+		fn get() -> u64 {
+			EGG_DEPOSIT.with(|v| *v.borrow())
+		}
+	}
+	pub struct LinearFeeCurve;
+	impl Convert<u16, u32> for LinearFeeCurve {
+		fn convert(generation: u16) -> u32 {
+			1 + generation as u32
+		}
+	}
+	parameter_types! {
+		pub const BreedCooldown: u64 = 10;
+		pub const MaxGeneration: u16 = 10;
+		pub const MinCreatorBalance: u64 = 100;
+		pub const DonorGeneCount: u32 = 4;
+		pub const RarityBiasedBreeding: bool = false;
+		pub const RankingInterval: u64 = 5;
+		pub const Treasury: u64 = 9;
+		pub const BurnReward: u64 = 10;
+		pub const StarterPackSize: u32 = 3;
+		pub const BreedingTokenReward: u32 = 1;
+		pub const TransferWindow: u64 = 10;
+		pub const MaxBatchSize: u32 = 3;
+		pub const MinBidIncrement: u64 = 5;
+		pub const VoucherSigner: u64 = 8;
+		pub const HatchFee: u64 = 20;
+		pub const MaxListingPrice: u64 = 500;
+		pub const SpeedupFee: u64 = 15;
+		pub const MaxLicenseTier: u8 = 3;
+		pub const LicenseUpgradeFee: u64 = 10;
+		pub const MaxOperatorsPerOwner: u32 = 2;
+		pub const EggRevealDelay: u64 = 3;
+		pub const EggRevealWindow: u64 = 5;
+		pub const LevelsPerFodder: u32 = 1;
+	}
+	/// The test offchain externalities can't take transactions, so none is ever built.
+	#[derive(Encode)]
+	pub struct TestExtrinsic(Call<Test>);
+	impl sr_primitives::traits::Extrinsic for TestExtrinsic {
+		type Call = Call<Test>;
+		fn is_signed(&self) -> Option<bool> {
+			None
+		}
+	}
+	impl Trait for Test {
+		type Event = TestEvent;
+		type Call = Call<Test>;
+		type UncheckedExtrinsic = TestExtrinsic;
+		type KittyIndex = u32;
+		type Currency = balances::Module<Test>;
+		type BreedCooldown = BreedCooldown;
+		type Gen0NoCooldown = Gen0NoCooldown;
+		type DeterministicGenetics = DeterministicGenetics;
+		type MaxGenerationGap = MaxGenerationGap;
+		type MaxGeneration = MaxGeneration;
+		type MinCreatorBalance = MinCreatorBalance;
+		type DonorGeneCount = DonorGeneCount;
+		type RarityBiasedBreeding = RarityBiasedBreeding;
+		type MaxKittiesPerAccount = MaxKittiesPerAccount;
+		type RankingInterval = RankingInterval;
+		type Treasury = Treasury;
+		type BurnReward = BurnReward;
+		type StarterPackSize = StarterPackSize;
+		type TransferFee = KittyTransferFee;
+		type BreedingTokenReward = BreedingTokenReward;
+		type BaseBreedingFee = BaseBreedingFee;
+		type BreedingFeeCurve = LinearFeeCurve;
+		type AntiSnipeWindow = AntiSnipeWindow;
+		type MaxTransfersPerWindow = MaxTransfersPerWindow;
+		type TransferWindow = TransferWindow;
+		type MaxBatchSize = MaxBatchSize;
+		type ResetCooldownOnTransfer = ResetCooldownOnTransfer;
+		type MinBidIncrement = MinBidIncrement;
+		type UniqueNames = UniqueNames;
+		type Signature = TestSignature;
+		type VoucherSigner = VoucherSigner;
+		type BalancedGenders = BalancedGenders;
+		type Incubation = Incubation;
+		type HatchFee = HatchFee;
+		type LockedTraitMask = LockedTraitMask;
+		type MaxListingPrice = MaxListingPrice;
+		type CreationDeposit = CreationDeposit;
+		type DepositFollowsKitty = DepositFollowsKitty;
+		type SpeedupFee = SpeedupFee;
+		type PresaleEnd = PresaleEnd;
+		type SameSpeciesOnly = SameSpeciesOnly;
+		type CompactBatchEvents = CompactBatchEvents;
+		type UseTombstones = UseTombstones;
+		type MaxLicenseTier = MaxLicenseTier;
+		type LicenseUpgradeFee = LicenseUpgradeFee;
+		type MaxOperatorsPerOwner = MaxOperatorsPerOwner;
+		type EggDeposit = EggDeposit;
+		type EggRevealDelay = EggRevealDelay;
+		type EggRevealWindow = EggRevealWindow;
+		type FreeGen0Breed = FreeGen0Breed;
+		type LevelsPerFodder = LevelsPerFodder;
+		type MaxPairOffspring = MaxPairOffspring;
+	}
+	type KittyModule = Module<Test>;
+	type Balances = balances::Module<Test>;
+
+	// This function basically just builds a genesis storage key/value store according to
+	// our desired mockup.
+	fn new_test_ext() -> runtime_io::TestExternalities<Blake2Hasher> {
+		new_test_ext_with_kitties(vec![])
+	}
+
+	fn new_test_ext_with_kitties(kitties: Vec<(u64, [u8; 16], u16)>) -> runtime_io::TestExternalities<Blake2Hasher> {
+		let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+		balances::GenesisConfig::<Test> {
+			balances: vec![(1, 1000), (2, 1000), (3, 1000), (4, 50)],
+			vesting: vec![],
+		}.assimilate_storage(&mut t).unwrap();
+		GenesisConfig::<Test> {
+			kitties,
+		}.assimilate_storage(&mut t).unwrap();
+		t.into()
+	}
+
+	fn last_event() -> TestEvent {
+		system::Module::<Test>::events().pop().map(|e| e.event).expect("Event expected")
+	}
+
+	#[test]
+	fn create_works() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+
+			assert_eq!(KittyModule::kitties_count(), 1);
+			assert!(KittyModule::kitties(0).is_some());
+			assert_eq!(KittyModule::kitty_owner(0), Some(1));
+			assert_eq!(KittyModule::owned_kitties_count(1), 1);
+			assert_eq!(KittyModule::kitties_of(&1), vec![0]);
+			assert_eq!(KittyModule::generation_of(0), 0);
+		});
+	}
+
+	#[test]
+	fn breed_works() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+
+			assert_ok!(KittyModule::breed(Origin::signed(1), 0, 1));
+
+			assert_eq!(KittyModule::kitties_count(), 3);
+			assert_eq!(KittyModule::kitty_owner(2), Some(1));
+			assert_eq!(KittyModule::generation_of(2), 1);
+			assert_eq!(KittyModule::parents_of(2), Some((0, 1)));
+		});
+	}
+
+	#[test]
+	fn breed_fails_for_invalid_parents() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+			assert_ok!(KittyModule::create(Origin::signed(2)));
+
+			assert_noop!(KittyModule::breed(Origin::signed(1), 0, 0), "Needs different parent");
+			assert_noop!(KittyModule::breed(Origin::signed(1), 0, 1), "Not owner of kitty");
+			assert_noop!(KittyModule::breed(Origin::signed(1), 0, 5), "Invalid kitty_id");
+		});
+	}
+
+	#[test]
+	fn offspring_gender_is_pinned_by_parents_and_selector() {
+		assert_eq!(offspring_gender(&[1; 16], &[2; 16], &[0xff; 16]), Gender::Female);
+		assert_eq!(offspring_gender(&[1; 16], &[2; 16], &[0; 16]), Gender::Male);
+		// Parent order matters
+		assert_eq!(offspring_gender(&[2; 16], &[1; 16], &[0xff; 16]), Gender::Male);
+	}
+
+	#[test]
+	fn bred_kitty_gender_follows_offspring_gender() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+
+			// Draw the value breed will get, then rewind the nonce
+			let nonce = KittyModule::nonce();
+			let selector = KittyModule::random_value(BREED_SUBJECT, &1);
+			<Nonce>::put(nonce);
+			assert_ok!(KittyModule::breed(Origin::signed(1), 0, 1));
+
+			let dna1 = KittyModule::kitties(0).unwrap().0;
+			let dna2 = KittyModule::kitties(1).unwrap().0;
+
+			assert_eq!(
+				KittyModule::kitties(2).unwrap().gender(),
+				offspring_gender(&dna1, &dna2, &selector),
+			);
+		});
+	}
+
+	#[test]
+	fn breed_sets_cooldown() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+			assert_ok!(KittyModule::breed(Origin::signed(1), 0, 1));
+
+			assert_eq!(KittyModule::cooldown_end_of(0), 10);
+			assert_eq!(KittyModule::cooldown_end_of(1), 10);
+			assert_noop!(KittyModule::breed(Origin::signed(1), 0, 1), "Kitty is on breeding cooldown");
+
+			system::Module::<Test>::set_block_number(10);
+			assert_ok!(KittyModule::breed(Origin::signed(1), 0, 1));
+		});
+	}
+
+	#[test]
+	fn gen0_parents_breed_back_to_back_when_exempt() {
+		with_externalities(&mut new_test_ext(), || {
+			GEN0_NO_COOLDOWN.with(|v| *v.borrow_mut() = true);
+
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+			assert_ok!(KittyModule::breed(Origin::signed(1), 0, 1));
+			assert_ok!(KittyModule::breed(Origin::signed(1), 0, 1));
+			assert_eq!(KittyModule::cooldown_end_of(0), 0);
+		});
+	}
+
+	#[test]
+	fn gen1_parents_keep_cooldown_when_gen0_exempt() {
+		with_externalities(&mut new_test_ext(), || {
+			GEN0_NO_COOLDOWN.with(|v| *v.borrow_mut() = true);
+
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+			assert_ok!(KittyModule::breed(Origin::signed(1), 0, 1));
+			assert_ok!(KittyModule::breed(Origin::signed(1), 0, 1));
+
+			// Kitties 2 and 3 are generation 1
+			assert_ok!(KittyModule::breed(Origin::signed(1), 2, 3));
+			assert_noop!(KittyModule::breed(Origin::signed(1), 2, 3), "Kitty is on breeding cooldown");
+		});
+	}
+
+	#[test]
+	fn transfer_kitty_works() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+
+			assert_ok!(KittyModule::transfer_kitty(Origin::signed(1), 2, 0));
+
+			assert_eq!(KittyModule::kitty_owner(0), Some(2));
+			assert_eq!(KittyModule::owned_kitties_count(1), 2);
+			assert_eq!(KittyModule::owned_kitties_count(2), 1);
+			// The remaining kitties of account 1 keep their order
+			assert_eq!(KittyModule::kitties_of(&1), vec![1, 2]);
+			assert_eq!(KittyModule::owned_kitties((1, None)), Some(KittyLinkedItem { prev: Some(2), next: Some(1) }));
+			assert_eq!(KittyModule::owned_kitties((1, Some(1))), Some(KittyLinkedItem { prev: None, next: Some(2) }));
+			assert_eq!(KittyModule::owned_kitties((1, Some(0))), None);
+			assert_eq!(KittyModule::kitties_of(&2), vec![0]);
+
+			assert_noop!(KittyModule::transfer_kitty(Origin::signed(1), 2, 0), "Not owner of kitty");
+		});
+	}
+
+	#[test]
+	fn approved_account_can_transfer_once() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+
+			assert_noop!(KittyModule::transfer_from(Origin::signed(3), 1, 3, 0), "Not approved to transfer kitty");
+			assert_ok!(KittyModule::approve(Origin::signed(1), 3, 0));
+			assert_ok!(KittyModule::transfer_from(Origin::signed(3), 1, 2, 0));
+
+			assert_eq!(KittyModule::kitty_owner(0), Some(2));
+			assert_eq!(KittyModule::approved_of(0), None);
+			assert_noop!(KittyModule::transfer_from(Origin::signed(3), 2, 3, 0), "Not approved to transfer kitty");
+		});
+	}
+
+	#[test]
+	fn operator_can_transfer_all_kitties_until_revoked() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+
+			assert_ok!(KittyModule::set_approval_for_all(Origin::signed(1), 3, true));
+			assert!(KittyModule::is_approved_for_all(&1, &3));
+
+			assert_ok!(KittyModule::transfer_from(Origin::signed(3), 1, 2, 0));
+			assert_ok!(KittyModule::transfer_from(Origin::signed(3), 1, 3, 1));
+			assert_eq!(KittyModule::kitty_owner(0), Some(2));
+			assert_eq!(KittyModule::kitty_owner(1), Some(3));
+
+			assert_ok!(KittyModule::set_approval_for_all(Origin::signed(1), 3, false));
+			assert!(!KittyModule::is_approved_for_all(&1, &3));
+			assert_noop!(KittyModule::transfer_from(Origin::signed(3), 1, 2, 2), "Not approved to transfer kitty");
+			assert_eq!(KittyModule::kitty_owner(2), Some(1));
+		});
+	}
+
+	#[test]
+	fn deterministic_genetics_reproduces_kitties_across_chains() {
+		DETERMINISTIC_GENETICS.with(|v| *v.borrow_mut() = true);
+
+		// Run the same calls on a fresh chain, starting at the given block
+		let run = |start_block: u64| with_externalities(&mut new_test_ext(), || {
+			system::Module::<Test>::set_block_number(start_block);
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+			assert_ok!(KittyModule::create(Origin::signed(2)));
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+			system::Module::<Test>::set_block_number(start_block + 1);
+			assert_ok!(KittyModule::breed(Origin::signed(1), 0, 2));
+
+			(0..4).map(|id| KittyModule::kitties(id).unwrap()).collect::<Vec<_>>()
+		});
+
+		let kitties = run(1);
+		assert_eq!(kitties, run(7));
+		assert_ne!(kitties[0], kitties[1]);
+		assert_ne!(kitties[0], kitties[2]);
+	}
+
+	#[test]
+	fn kitties_by_ids_preserves_order() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+			assert_ok!(KittyModule::create(Origin::signed(2)));
+
+			let kitty0 = KittyModule::kitties(0);
+			let kitty1 = KittyModule::kitties(1);
+			assert_eq!(
+				KittyModule::kitties_by_ids(vec![1, 5, 0, 1, 2]),
+				vec![kitty1.clone(), None, kitty0, kitty1, None],
+			);
+		});
+	}
+
+	#[test]
+	fn kitties_by_ids_is_bounded() {
+		with_externalities(&mut new_test_ext(), || {
+			let ids = vec![0; MAX_QUERY_LENGTH + 10];
+			assert_eq!(KittyModule::kitties_by_ids(ids).len(), MAX_QUERY_LENGTH);
+		});
+	}
+
+	#[test]
+	fn retired_kitty_cannot_breed() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+
+			assert_noop!(KittyModule::retire(Origin::signed(2), 0), "Not owner of kitty");
+			assert_ok!(KittyModule::retire(Origin::signed(1), 0));
+			assert_eq!(last_event(), TestEvent::kitties(RawEvent::Retired(0)));
+
+			assert!(KittyModule::is_retired(0));
+			assert_noop!(KittyModule::breed(Origin::signed(1), 0, 1), "Kitty is retired");
+			assert_noop!(KittyModule::breed(Origin::signed(1), 1, 0), "Kitty is retired");
+		});
+	}
+
+	#[test]
+	fn retirement_is_permanent() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+			assert_ok!(KittyModule::retire(Origin::signed(1), 0));
+
+			assert_noop!(KittyModule::retire(Origin::signed(1), 0), "Kitty is already retired");
+
+			// Changing hands does not lift the retirement
+			assert_ok!(KittyModule::transfer_kitty(Origin::signed(1), 2, 0));
+			assert!(KittyModule::is_retired(0));
+		});
+	}
+
+	// Breeds kitties 0 and 1 twice into generation 1 kitties 2 and 3, then those into the
+	// generation 2 kitty 4, and creates the generation 0 kitty 5.
+	fn setup_generations() {
+		assert_ok!(KittyModule::create(Origin::signed(1)));
+		assert_ok!(KittyModule::create(Origin::signed(1)));
+		assert_ok!(KittyModule::breed(Origin::signed(1), 0, 1));
+		system::Module::<Test>::set_block_number(10);
+		assert_ok!(KittyModule::breed(Origin::signed(1), 0, 1));
+		assert_ok!(KittyModule::breed(Origin::signed(1), 2, 3));
+		assert_ok!(KittyModule::create(Origin::signed(1)));
+		system::Module::<Test>::set_block_number(20);
+
+		assert_eq!(KittyModule::generation_of(4), 2);
+		assert_eq!(KittyModule::generation_of(5), 0);
+	}
+
+	#[test]
+	fn breed_within_generation_gap_works() {
+		with_externalities(&mut new_test_ext(), || {
+			MAX_GENERATION_GAP.with(|v| *v.borrow_mut() = 2);
+			setup_generations();
+
+			assert_ok!(KittyModule::breed(Origin::signed(1), 4, 5));
+			assert_eq!(KittyModule::generation_of(6), 3);
+		});
+	}
+
+	#[test]
+	fn breed_over_generation_gap_fails() {
+		with_externalities(&mut new_test_ext(), || {
+			MAX_GENERATION_GAP.with(|v| *v.borrow_mut() = 1);
+			setup_generations();
+
+			assert_noop!(KittyModule::breed(Origin::signed(1), 4, 5), "Generation gap too large");
+			assert_noop!(KittyModule::breed(Origin::signed(1), 5, 4), "Generation gap too large");
+			// A gap of one is still allowed
+			assert_ok!(KittyModule::breed(Origin::signed(1), 4, 2));
+		});
+	}
+
+	#[test]
+	fn set_price_requires_owner() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+
+			assert_noop!(KittyModule::set_price(Origin::signed(2), 0, Some(10)), "Not owner of kitty");
+			assert_ok!(KittyModule::set_price(Origin::signed(1), 0, Some(10)));
+			assert_eq!(KittyModule::kitty_price(0), Some(10));
+			assert_eq!(last_event(), TestEvent::kitties(RawEvent::PriceSet(1, 0, 10)));
+		});
+	}
+
+	#[test]
+	fn account_summary_works() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+			assert_ok!(KittyModule::create(Origin::signed(2)));
+			assert_ok!(KittyModule::transfer_kitty(Origin::signed(2), 1, 2));
+
+			assert_ok!(KittyModule::set_price(Origin::signed(1), 0, Some(10)));
+			assert_ok!(KittyModule::set_price(Origin::signed(1), 2, Some(25)));
+
+			assert_eq!(KittyModule::account_summary(&1), AccountSummary {
+				owned: vec![0, 1, 2],
+				total_owned: 3,
+				created_count: 2,
+				listed: vec![(0, 10), (2, 25)],
+				total_listed_value: 35,
+			});
+			assert_eq!(KittyModule::account_summary(&2), AccountSummary {
+				owned: vec![],
+				total_owned: 0,
+				created_count: 1,
+				listed: vec![],
+				total_listed_value: 0,
+			});
+		});
+	}
+
+	#[test]
+	fn unlisting_emits_event() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+
+			assert_noop!(KittyModule::set_price(Origin::signed(1), 0, None), "Kitty is not listed");
+
+			assert_ok!(KittyModule::set_price(Origin::signed(1), 0, Some(10)));
+			assert_ok!(KittyModule::set_price(Origin::signed(1), 0, None));
+			assert_eq!(last_event(), TestEvent::kitties(RawEvent::Unlisted(0)));
+			assert_eq!(KittyModule::kitty_price(0), None);
+		});
+	}
+
+	#[test]
+	fn genesis_kitties_keep_their_generation() {
+		with_externalities(&mut new_test_ext_with_kitties(vec![(1, [1; 16], 0), (2, [2; 16], 5)]), || {
+			assert_eq!(KittyModule::kitties_count(), 2);
+			assert_eq!(KittyModule::kitties(1), Some(Kitty([2; 16])));
+			assert_eq!(KittyModule::kitty_owner(1), Some(2));
+			assert_eq!(KittyModule::generation_of(0), 0);
+			assert_eq!(KittyModule::generation_of(1), 5);
+		});
+	}
+
+	#[test]
+	fn breed_up_to_max_generation_works() {
+		with_externalities(&mut new_test_ext_with_kitties(vec![(1, [1; 16], 9), (1, [2; 16], 9)]), || {
+			assert_ok!(KittyModule::breed(Origin::signed(1), 0, 1));
+			assert_eq!(KittyModule::generation_of(2), MaxGeneration::get());
+		});
+	}
+
+	#[test]
+	fn breed_past_max_generation_fails() {
+		with_externalities(&mut new_test_ext_with_kitties(vec![(1, [1; 16], 10), (1, [2; 16], 9)]), || {
+			assert_noop!(KittyModule::breed(Origin::signed(1), 0, 1), "Max generation reached");
+		});
+	}
+
+	#[test]
+	#[should_panic(expected = "Genesis kitty generation above MaxGeneration")]
+	fn genesis_kitty_above_max_generation_panics() {
+		new_test_ext_with_kitties(vec![(1, [1; 16], 11)]);
+	}
+
+	#[test]
+	fn kitty_age_follows_blocks() {
+		with_externalities(&mut new_test_ext(), || {
+			system::Module::<Test>::set_block_number(3);
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+
+			assert_eq!(KittyModule::birth_block_of(0), 3);
+			assert_eq!(KittyModule::kitty_age(0), Some(0));
+
+			system::Module::<Test>::set_block_number(8);
+			assert_eq!(KittyModule::kitty_age(0), Some(5));
+			assert_eq!(KittyModule::kitty_age(1), None);
+		});
+	}
+
+	#[test]
+	fn unbid_expired_auction_is_swept() {
+		with_externalities(&mut new_test_ext(), || {
+			system::Module::<Test>::set_block_number(1);
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+			assert_ok!(KittyModule::create_auction(Origin::signed(1), 0, 10, 5));
+
+			assert_eq!(KittyModule::auctions_ending_at(6), vec![0]);
+			assert_noop!(KittyModule::transfer_kitty(Origin::signed(1), 2, 0), "Kitty is locked");
+
+			// Nothing happens before the auction ends
+			KittyModule::on_initialize(5);
+			assert!(KittyModule::auction_of(0).is_some());
+
+			system::Module::<Test>::set_block_number(6);
+			KittyModule::on_initialize(6);
+
+			assert_eq!(last_event(), TestEvent::kitties(RawEvent::AuctionCancelled(0)));
+			assert_eq!(KittyModule::auction_of(0), None);
+			assert!(KittyModule::auctions_ending_at(6).is_empty());
+			assert!(!KittyModule::is_locked(0));
+			assert_ok!(KittyModule::transfer_kitty(Origin::signed(1), 2, 0));
+		});
+	}
+
+	#[test]
+	fn auction_with_bids_is_settled_on_close() {
+		with_externalities(&mut new_test_ext(), || {
+			system::Module::<Test>::set_block_number(1);
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+			assert_ok!(KittyModule::create_auction(Origin::signed(1), 0, 10, 5));
+
+			assert_noop!(KittyModule::bid(Origin::signed(1), 0, 20), "Cannot bid on own auction");
+			assert_noop!(KittyModule::bid(Origin::signed(2), 0, 5), "Bid below start price");
+			assert_ok!(KittyModule::bid(Origin::signed(2), 0, 20));
+			assert_noop!(KittyModule::bid(Origin::signed(3), 0, 20), "Bid not higher than current bid");
+			assert_ok!(KittyModule::bid(Origin::signed(3), 0, 30));
+
+			// The outbid amount is given back
+			assert_eq!(Balances::reserved_balance(&2), 0);
+			assert_eq!(Balances::reserved_balance(&3), 30);
+
+			assert_noop!(KittyModule::close_auction(Origin::signed(2), 0), "Auction has not ended");
+
+			// The sweep leaves auctions with bids alone
+			system::Module::<Test>::set_block_number(6);
+			KittyModule::on_initialize(6);
+			assert!(KittyModule::auction_of(0).is_some());
+			assert_noop!(KittyModule::bid(Origin::signed(2), 0, 40), "Auction has ended");
+
+			assert_ok!(KittyModule::close_auction(Origin::signed(2), 0));
+			assert_eq!(last_event(), TestEvent::kitties(RawEvent::AuctionClosed(0, 3, 30)));
+			assert_eq!(KittyModule::kitty_owner(0), Some(3));
+			assert!(!KittyModule::is_locked(0));
+			assert_eq!(Balances::free_balance(&1), 1030);
+			assert_eq!(Balances::free_balance(&3), 970);
+			assert_eq!(Balances::reserved_balance(&3), 0);
+		});
+	}
+
+	#[test]
+	fn create_requires_min_balance() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_noop!(KittyModule::create(Origin::signed(4)), "Balance too low to create kitty");
+			assert_noop!(KittyModule::create(Origin::signed(5)), "Balance too low to create kitty");
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+		});
+	}
+
+	#[test]
+	fn kitties_id_by_dna_works() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+			assert_ok!(KittyModule::create(Origin::signed(2)));
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+
+			for id in 0..3 {
+				let dna = KittyModule::kitties(id).unwrap().0;
+				assert_eq!(KittyModule::kitties_id_by_dna(dna), Some(id));
+			}
+			assert_eq!(KittyModule::kitties_id_by_dna([0; 16]), None);
+		});
+	}
+
+	#[test]
+	#[should_panic(expected = "Duplicate genesis kitty DNA")]
+	fn duplicate_genesis_dna_panics() {
+		new_test_ext_with_kitties(vec![(1, [1; 16], 0), (2, [1; 16], 0)]);
+	}
+
+	#[test]
+	fn breed_with_donor_splices_donor_genes() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+
+			assert_ok!(KittyModule::breed_with_donor(Origin::signed(1), 0, 1, 2));
+
+			let donor_dna = KittyModule::kitties(2).unwrap().0;
+			let child_dna = KittyModule::kitties(3).unwrap().0;
+			assert_eq!(child_dna[12..], donor_dna[12..]);
+
+			// Only the parents incur a cooldown
+			assert_eq!(KittyModule::cooldown_end_of(0), 10);
+			assert_eq!(KittyModule::cooldown_end_of(1), 10);
+			assert_eq!(KittyModule::cooldown_end_of(2), 0);
+		});
+	}
+
+	#[test]
+	fn breed_with_donor_checks_donor() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+			assert_ok!(KittyModule::create(Origin::signed(2)));
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+
+			assert_noop!(KittyModule::breed_with_donor(Origin::signed(1), 0, 1, 9), "Invalid donor");
+			assert_noop!(KittyModule::breed_with_donor(Origin::signed(1), 0, 1, 1), "Donor must not be a parent");
+			assert_noop!(KittyModule::breed_with_donor(Origin::signed(1), 0, 1, 2), "Not owner of donor");
+
+			assert_ok!(KittyModule::breed(Origin::signed(1), 3, 4));
+			assert_noop!(KittyModule::breed_with_donor(Origin::signed(1), 0, 1, 3), "Donor is on breeding cooldown");
+		});
+	}
+
+	#[test]
+	fn cancelled_approval_blocks_spender() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+
+			assert_noop!(KittyModule::cancel_approval(Origin::signed(1), 0), "Kitty has no approval");
+			assert_ok!(KittyModule::approve(Origin::signed(1), 3, 0));
+			assert_eq!(last_event(), TestEvent::kitties(RawEvent::Approval(1, Some(3), 0)));
+
+			assert_noop!(KittyModule::cancel_approval(Origin::signed(3), 0), "Not owner of kitty");
+			assert_ok!(KittyModule::cancel_approval(Origin::signed(1), 0));
+			assert_eq!(last_event(), TestEvent::kitties(RawEvent::Approval(1, None, 0)));
+
+			assert_eq!(KittyModule::approved_of(0), None);
+			assert_noop!(KittyModule::transfer_from(Origin::signed(3), 1, 3, 0), "Not approved to transfer kitty");
+		});
+	}
+
+	#[test]
+	fn ownership_changes_clear_approval() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+			assert_ok!(KittyModule::approve(Origin::signed(1), 3, 0));
+
+			assert_ok!(KittyModule::transfer_kitty(Origin::signed(1), 2, 0));
+			assert_eq!(KittyModule::approved_of(0), None);
+
+			// Kitties won at auction don't keep approvals either
+			system::Module::<Test>::set_block_number(1);
+			assert_ok!(KittyModule::approve(Origin::signed(2), 3, 0));
+			assert_ok!(KittyModule::create_auction(Origin::signed(2), 0, 10, 1));
+			assert_ok!(KittyModule::bid(Origin::signed(1), 0, 10));
+			system::Module::<Test>::set_block_number(2);
+			assert_ok!(KittyModule::close_auction(Origin::signed(1), 0));
+			assert_eq!(KittyModule::kitty_owner(0), Some(1));
+			assert_eq!(KittyModule::approved_of(0), None);
+		});
+	}
+
+	#[test]
+	fn rarity_counts_rare_traits() {
+		let mut dna = [0x10; 16];
+		assert_eq!(Kitty(dna).rarity(), 0);
+		dna[3] = 0xf0;
+		dna[9] = 0xff;
+		assert_eq!(Kitty(dna).rarity(), 2);
+	}
+
+	#[test]
+	fn parent_selector_differs_per_pairing() {
+		let random = [0x5a; 16];
+		let (dna0, dna1, dna2) = ([0x10; 16], [0x21; 16], [0x32; 16]);
+		let selector = parent_selector(random, (0u32, &dna0), (1u32, &dna1));
+
+		assert_eq!(selector, parent_selector(random, (0u32, &dna0), (1u32, &dna1)));
+		assert_ne!(selector, parent_selector(random, (0u32, &dna0), (2u32, &dna2)));
+		assert_ne!(selector, parent_selector(random, (1u32, &dna1), (0u32, &dna0)));
+	}
+
+	#[test]
+	fn biased_selector_is_unbiased_for_equal_rarity() {
+		let random = [0x5a; 16];
+		assert_eq!(biased_selector(3, 3, random), random);
+		assert_eq!(biased_selector(0, 0, random), random);
+	}
+
+	#[test]
+	fn biased_selector_skews_towards_rarer_parent() {
+		let random = [0x5a; 16];
+
+		// A rarity of 3 against 1 takes half of the genes from the first parent
+		let mut expected = [0x5a; 16];
+		expected[..8].copy_from_slice(&[0xff; 8]);
+		assert_eq!(biased_selector(3, 1, random), expected);
+
+		// And from the second parent when it is the rarer one
+		expected[..8].copy_from_slice(&[0x00; 8]);
+		assert_eq!(biased_selector(1, 3, random), expected);
+
+		// A parent without rare traits passes nothing on against a rare one
+		assert_eq!(biased_selector(0, 2, random), [0x00; 16]);
+	}
+
+	#[test]
+	fn mint_respects_max_kitties_per_account() {
+		with_externalities(&mut new_test_ext(), || {
+			MAX_KITTIES_PER_ACCOUNT.with(|v| *v.borrow_mut() = 2);
+
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+			assert_noop!(KittyModule::create(Origin::signed(1)), "Too many kitties owned");
+			assert_noop!(KittyModule::breed(Origin::signed(1), 0, 1), "Too many kitties owned");
+		});
+	}
+
+	#[test]
+	fn safe_transfer_rejects_recipient_at_cap() {
+		with_externalities(&mut new_test_ext(), || {
+			MAX_KITTIES_PER_ACCOUNT.with(|v| *v.borrow_mut() = 1);
+
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+			assert_ok!(KittyModule::create(Origin::signed(2)));
+
+			assert_noop!(KittyModule::safe_transfer(Origin::signed(1), 2, 0), "Recipient has too many kitties");
+			assert_ok!(KittyModule::safe_transfer(Origin::signed(1), 3, 0));
+			assert_eq!(KittyModule::kitty_owner(0), Some(3));
+		});
+	}
+
+	#[test]
+	fn safe_transfer_rejects_frozen_recipient() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+
+			assert_noop!(KittyModule::set_frozen(Origin::signed(1), 2, true), "bad origin: expected to be a root origin");
+			assert_ok!(KittyModule::set_frozen(Origin::ROOT, 2, true));
+			assert_noop!(KittyModule::safe_transfer(Origin::signed(1), 2, 0), "Recipient account is frozen");
+
+			assert_ok!(KittyModule::set_frozen(Origin::ROOT, 2, false));
+			assert_ok!(KittyModule::safe_transfer(Origin::signed(1), 2, 0));
+		});
+	}
+
+	#[test]
+	fn rarity_ranking_orders_by_rarity_then_id() {
+		let mut rare = [0x10; 16];
+		rare[1] = 0xff;
+		let mut rarer = rare;
+		rarer[2] = 0xf8;
+		let kitties = vec![(1, [0x10; 16], 0), (1, rare, 0), (2, rarer, 0), (2, [0x20; 16], 0)];
+
+		with_externalities(&mut new_test_ext_with_kitties(kitties), || {
+			assert_eq!(KittyModule::rarity_ranking(), vec![2, 1, 0, 3]);
+		});
+	}
+
+	#[test]
+	fn offchain_worker_writes_rarity_ranking() {
+		let mut rare = [0x10; 16];
+		rare[1] = 0xff;
+		let mut t = new_test_ext_with_kitties(vec![(1, [0x10; 16], 0), (1, rare, 0)]);
+		let (offchain, state) = TestOffchainExt::new();
+		t.set_offchain_externalities(offchain);
+
+		with_externalities(&mut t, || {
+			// Rankings are only published every `RankingInterval` blocks
+			KittyModule::offchain_worker(4);
+			assert!(state.read().persistent_storage.get(b"", RARITY_RANKING_KEY).is_none());
+
+			KittyModule::offchain_worker(5);
+		});
+
+		let ranking = state.read().persistent_storage.get(b"", RARITY_RANKING_KEY).unwrap();
+		assert_eq!(Vec::<u32>::decode(&mut &ranking[..]).unwrap(), vec![1, 0]);
+	}
+
+	#[test]
+	fn unsigned_rarity_ranking_is_validated() {
+		let mut rare = [0x10; 16];
+		rare[1] = 0xff;
+		with_externalities(&mut new_test_ext_with_kitties(vec![(1, [0x10; 16], 0), (1, rare, 0)]), || {
+			system::Module::<Test>::set_block_number(6);
+			let call = Call::submit_rarity_ranking(vec![1, 0], 5);
+			assert!(KittyModule::validate_unsigned(&call).is_ok());
+			// Only the ranking call is accepted unsigned
+			assert!(KittyModule::validate_unsigned(&Call::create()).is_err());
+			assert!(KittyModule::validate_unsigned(&Call::submit_rarity_ranking(vec![0, 1], 5)).is_err());
+			assert!(KittyModule::validate_unsigned(&Call::submit_rarity_ranking(vec![1, 0], 4)).is_err());
+			assert!(KittyModule::validate_unsigned(&Call::submit_rarity_ranking(vec![1, 7], 5)).is_err());
+			assert!(KittyModule::validate_unsigned(&Call::submit_rarity_ranking(vec![1, 0], 10)).is_err());
+
+			assert_noop!(
+				KittyModule::submit_rarity_ranking(Origin::signed(1), vec![1, 0], 5),
+				"bad origin: expected to be no origin"
+			);
+			assert_noop!(KittyModule::submit_rarity_ranking(Origin::NONE, vec![0, 1], 5), "Ranking out of order");
+			assert_ok!(KittyModule::submit_rarity_ranking(Origin::NONE, vec![1, 0], 5));
+			assert_eq!(KittyModule::published_rarity_ranking(), vec![1, 0]);
+			assert_eq!(KittyModule::ranking_computed_at(), 5);
+
+			// A ranking is accepted once per ranking block
+			assert!(KittyModule::validate_unsigned(&call).is_err());
+		});
+	}
+
+	#[test]
+	fn burn_pays_reward_from_funded_treasury() {
+		with_externalities(&mut new_test_ext_with_kitties(vec![(1, [0x10; 16], 0)]), || {
+			Balances::make_free_balance_be(&9, 100);
+
+			assert_noop!(KittyModule::burn(Origin::signed(2), 0), "Not owner of kitty");
+			assert_ok!(KittyModule::burn(Origin::signed(1), 0));
+
+			assert_eq!(last_event(), TestEvent::kitties(RawEvent::Burned(1, 0, 10)));
+			assert_eq!(Balances::free_balance(&1), 1010);
+			assert_eq!(Balances::free_balance(&9), 90);
+			assert_eq!(KittyModule::kitties(0), None);
+			assert_eq!(KittyModule::kitty_owner(0), None);
+			assert_eq!(KittyModule::owned_kitties_count(&1), 0);
+			assert_eq!(KittyModule::kitties_id_by_dna([0x10; 16]), None);
+		});
+	}
+
+	#[test]
+	fn burn_without_treasury_funds_skips_reward() {
+		with_externalities(&mut new_test_ext_with_kitties(vec![(1, [0x10; 16], 0)]), || {
+			assert_ok!(KittyModule::burn(Origin::signed(1), 0));
+
+			assert_eq!(last_event(), TestEvent::kitties(RawEvent::Burned(1, 0, 0)));
+			assert_eq!(Balances::free_balance(&1), 1000);
+			assert_eq!(KittyModule::kitties(0), None);
+			assert_noop!(KittyModule::burn(Origin::signed(1), 0), "Invalid kitty_id");
+		});
+	}
+
+	#[test]
+	fn transfer_to_full_account_overflows() {
+		with_externalities(&mut new_test_ext_with_kitties(vec![(1, [0x10; 16], 0)]), || {
+			<OwnedKittiesCount<Test>>::insert(2, u32::max_value());
+
+			assert_noop!(KittyModule::transfer_kitty(Origin::signed(1), 2, 0), "Owned kitties count overflow");
+			assert_eq!(KittyModule::kitty_owner(0), Some(1));
+		});
+	}
+
+	#[test]
+	fn transfer_from_empty_count_underflows() {
+		with_externalities(&mut new_test_ext_with_kitties(vec![(1, [0x10; 16], 0)]), || {
+			<OwnedKittiesCount<Test>>::insert(1, 0);
+
+			assert_eq!(KittyModule::owned_count_minus_one(&1), Err("Owned kitties count inconsistent"));
+			assert_noop!(KittyModule::transfer_kitty(Origin::signed(1), 2, 0), "Owned kitties count inconsistent");
+			assert_eq!(KittyModule::owned_kitties_count(&2), 0);
+		});
+	}
+
+	#[test]
+	fn missing_owned_list_entry_is_explicit() {
+		with_externalities(&mut new_test_ext_with_kitties(vec![(1, [0x10; 16], 0)]), || {
+			assert!(KittyModule::owned_kitties((1, Some(0))).is_some());
+			assert_eq!(KittyModule::owned_kitties((1, Some(1))), None);
+
+			<OwnedKitties<Test>>::remove((1, Some(0)));
+			assert_eq!(KittyModule::try_state(), Err("Kitty not in owner list"));
+			assert_noop!(KittyModule::transfer_kitty(Origin::signed(1), 2, 0), "Kitty not in owner list");
+		});
+	}
+
+	#[test]
+	fn claim_starter_pack_works_once() {
+		with_externalities(&mut new_test_ext(), || {
+			// The starter pack doesn't require the creator balance
+			assert_ok!(KittyModule::claim_starter_pack(Origin::signed(4)));
+
+			assert_eq!(KittyModule::owned_kitties_count(&4), 3);
+			assert_eq!(KittyModule::kitties_count(), 3);
+			assert!(KittyModule::starter_pack_claimed(&4));
+
+			assert_noop!(KittyModule::claim_starter_pack(Origin::signed(4)), "Starter pack already claimed");
+		});
+	}
+
+	#[test]
+	fn offering_wishlisted_sire_notifies_wishers() {
+		with_externalities(&mut new_test_ext_with_kitties(vec![(1, [0x10; 16], 0), (1, [0x20; 16], 0)]), || {
+			assert_ok!(KittyModule::wish_sire(Origin::signed(2), 0));
+			assert_noop!(KittyModule::wish_sire(Origin::signed(2), 0), "Sire already on wishlist");
+			assert_eq!(KittyModule::wishlist(&2), vec![0]);
+
+			assert_noop!(KittyModule::offer_sire(Origin::signed(2), 0), "Not owner of kitty");
+			assert_ok!(KittyModule::offer_sire(Origin::signed(1), 0));
+
+			assert!(KittyModule::is_sire_offered(0));
+			assert_eq!(last_event(), TestEvent::kitties(RawEvent::SireAvailable(0, vec![2])));
+		});
+	}
+
+	#[test]
+	fn sire_wishers_are_bounded_and_cleared() {
+		with_externalities(&mut new_test_ext_with_kitties(vec![(1, [0x10; 16], 0), (1, [0x20; 16], 0)]), || {
+			for who in 0..MAX_WISHERS_PER_SIRE as u64 {
+				assert_ok!(KittyModule::wish_sire(Origin::signed(100 + who), 0));
+			}
+			assert_noop!(KittyModule::wish_sire(Origin::signed(2), 0), "Too many wishers for sire");
+
+			assert_noop!(KittyModule::unwish_sire(Origin::signed(2), 0), "Sire not on wishlist");
+			assert_ok!(KittyModule::unwish_sire(Origin::signed(100), 0));
+			assert!(KittyModule::wishlist(&100).is_empty());
+			assert!(!KittyModule::sire_wishers(0).contains(&100));
+			assert_ok!(KittyModule::wish_sire(Origin::signed(2), 0));
+			assert_ok!(KittyModule::wish_sire(Origin::signed(2), 1));
+
+			assert_ok!(KittyModule::burn(Origin::signed(1), 0));
+			assert!(KittyModule::sire_wishers(0).is_empty());
+			assert_eq!(KittyModule::wishlist(&2), vec![1]);
+			assert!(KittyModule::wishlist(&101).is_empty());
+		});
+	}
+
+	#[test]
+	fn transfer_kitty_charges_fee() {
+		with_externalities(&mut new_test_ext_with_kitties(vec![(1, [0x10; 16], 0), (4, [0x20; 16], 0)]), || {
+			KITTY_TRANSFER_FEE.with(|v| *v.borrow_mut() = 100);
+
+			assert_ok!(KittyModule::transfer_kitty(Origin::signed(1), 2, 0));
+			assert_eq!(Balances::free_balance(&1), 900);
+			assert_eq!(Balances::free_balance(&9), 100);
+
+			// Account 4 only has 50
+			assert_noop!(KittyModule::transfer_kitty(Origin::signed(4), 2, 1), "Cannot pay transfer fee");
+			assert_eq!(KittyModule::kitty_owner(1), Some(4));
+		});
+	}
+
+	#[test]
+	fn kitties_snapshot_pages_through_all_kitties() {
+		let kitties = vec![(1, [0x10; 16], 0), (2, [0x20; 16], 0), (1, [0x30; 16], 0), (3, [0x40; 16], 0), (2, [0x50; 16], 0)];
+		with_externalities(&mut new_test_ext_with_kitties(kitties.clone()), || {
+			let mut snapshot = Vec::new();
+			let mut start = 0;
+			loop {
+				let page = KittyModule::kitties_snapshot(start, 2);
+				assert!(page.len() <= 2);
+				match page.last() {
+					Some(last) => start = last.0 + 1,
+					None => break,
+				}
+				snapshot.extend(page);
+			}
+
+			let expected: Vec<_> = kitties.into_iter().enumerate()
+				.map(|(id, (owner, dna, _))| (id as u32, Kitty(dna), owner))
+				.collect();
+			assert_eq!(snapshot, expected);
+		});
+	}
+
+	#[test]
+	fn breeding_earns_token() {
+		with_externalities(&mut new_test_ext_with_kitties(vec![(1, [0x10; 16], 0), (1, [0x21; 16], 0)]), || {
+			assert_eq!(KittyModule::tokens(&1), 0);
+			assert_ok!(KittyModule::breed(Origin::signed(1), 0, 1));
+			assert_eq!(KittyModule::tokens(&1), 1);
+		});
+	}
+
+	#[test]
+	fn mutate_dna_changes_one_byte() {
+		let dna = [0x10; 16];
+		// Index 1 + 15 % 15 = 1, the random byte equals the current one so it is inverted
+		let mutated = mutate_dna(dna, [15, 0x10, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+		assert_eq!(mutated[1], !0x10);
+		assert_eq!(mutated.iter().zip(dna.iter()).filter(|(a, b)| a != b).count(), 1);
+	}
+
+	#[test]
+	fn spend_breeding_token_mutates_kitty() {
+		with_externalities(&mut new_test_ext_with_kitties(vec![(1, [0x10; 16], 0)]), || {
+			assert_noop!(KittyModule::spend_breeding_token(Origin::signed(1), 0), "No breeding token");
+
+			<BreedingTokens<Test>>::insert(1, 1);
+			assert_ok!(KittyModule::spend_breeding_token(Origin::signed(1), 0));
+
+			let dna = KittyModule::kitties(0).unwrap().0;
+			assert_ne!(dna, [0x10; 16]);
+			assert_eq!(dna[0], 0x10);
+			assert_eq!(KittyModule::tokens(&1), 0);
+			assert_eq!(KittyModule::kitties_id_by_dna(dna), Some(0));
+			assert_eq!(KittyModule::kitties_id_by_dna([0x10; 16]), None);
+			assert_eq!(last_event(), TestEvent::kitties(RawEvent::Mutated(0)));
+		});
+	}
+
+	#[test]
+	fn active_listings_skips_unlisted_kitties() {
+		let kitties = vec![(1, [0x10; 16], 0), (2, [0x20; 16], 0), (1, [0x30; 16], 0), (3, [0x40; 16], 0)];
+		with_externalities(&mut new_test_ext_with_kitties(kitties), || {
+			assert_ok!(KittyModule::set_price(Origin::signed(1), 0, Some(10)));
+			assert_ok!(KittyModule::set_price(Origin::signed(2), 1, Some(20)));
+			assert_ok!(KittyModule::set_price(Origin::signed(3), 3, Some(30)));
+			assert_ok!(KittyModule::set_price(Origin::signed(2), 1, None));
+
+			assert_eq!(KittyModule::active_listings(0, 10), vec![(0, 1, 10), (3, 3, 30)]);
+			assert_eq!(KittyModule::active_listings(1, 10), vec![(3, 3, 30)]);
+			assert_eq!(KittyModule::active_listings(0, 1), vec![(0, 1, 10)]);
+		});
+	}
+
+	#[test]
+	fn breeding_fee_follows_curve() {
+		with_externalities(&mut new_test_ext(), || {
+			BASE_BREEDING_FEE.with(|v| *v.borrow_mut() = 10);
+
+			assert_eq!(KittyModule::breeding_fee(0), 10);
+			assert_eq!(KittyModule::breeding_fee(5), 60);
+			assert_eq!(KittyModule::breeding_fee(10), 110);
+		});
+	}
+
+	#[test]
+	fn breed_charges_breeding_fee() {
+		with_externalities(&mut new_test_ext_with_kitties(vec![(1, [0x10; 16], 5), (1, [0x21; 16], 5)]), || {
+			BASE_BREEDING_FEE.with(|v| *v.borrow_mut() = 10);
+
+			assert_ok!(KittyModule::breed(Origin::signed(1), 0, 1));
+			assert_eq!(Balances::free_balance(&1), 940);
+			assert_eq!(Balances::free_balance(&9), 60);
+		});
+	}
+
+	#[test]
+	fn clone_kitty_copies_attributes() {
+		with_externalities(&mut new_test_ext_with_kitties(vec![(1, [0x10; 16], 2), (1, [0x21; 16], 2)]), || {
+			assert_ok!(KittyModule::breed(Origin::signed(1), 0, 1));
+			let source = KittyModule::kitties(2).unwrap();
+
+			assert_noop!(KittyModule::clone_kitty(Origin::signed(1), 2, 3), "bad origin: expected to be a root origin");
+			assert_ok!(KittyModule::clone_kitty(Origin::ROOT, 2, 3));
+
+			let clone = KittyModule::kitties(3).unwrap();
+			assert_eq!(clone.0[..15], source.0[..15]);
+			assert_eq!(clone.0[15], source.0[15].wrapping_add(1));
+			assert_eq!(KittyModule::kitty_owner(3), Some(3));
+			assert_eq!(KittyModule::generation_of(3), 3);
+			assert_eq!(KittyModule::parents_of(3), Some((0, 1)));
+			assert_eq!(KittyModule::kitties_id_by_dna(clone.0), Some(3));
+		});
+	}
+
+	#[test]
+	fn owners_of_preserves_order() {
+		with_externalities(&mut new_test_ext_with_kitties(vec![(1, [0x10; 16], 0), (2, [0x20; 16], 0)]), || {
+			assert_eq!(KittyModule::owners_of(vec![1, 5, 0, 1]), vec![Some(2), None, Some(1), Some(2)]);
+			assert_eq!(KittyModule::owners_of(vec![0; MAX_QUERY_LENGTH + 1]).len(), MAX_QUERY_LENGTH);
+		});
+	}
+
+	#[test]
+	fn random_value_is_unique_within_a_block() {
+		with_externalities(&mut new_test_ext(), || {
+			let values: Vec<_> = (0..3).map(|_| KittyModule::random_value(CREATE_SUBJECT, &1)).collect();
+			assert_ne!(values[0], values[1]);
+			assert_ne!(values[1], values[2]);
+			assert_eq!(KittyModule::nonce(), 3);
+
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+			assert_ne!(KittyModule::kitties(0), KittyModule::kitties(1));
+			assert_ne!(KittyModule::kitties(1), KittyModule::kitties(2));
+		});
+	}
+
+	#[test]
+	fn late_bid_extends_auction() {
+		with_externalities(&mut new_test_ext_with_kitties(vec![(1, [0x10; 16], 0)]), || {
+			ANTI_SNIPE_WINDOW.with(|v| *v.borrow_mut() = 3);
+			system::Module::<Test>::set_block_number(1);
+			assert_ok!(KittyModule::create_auction(Origin::signed(1), 0, 10, 10));
+
+			// Block 11 is well outside the window
+			assert_ok!(KittyModule::bid(Origin::signed(2), 0, 10));
+			assert_eq!(KittyModule::auction_of(0).unwrap().end_block, 11);
+
+			system::Module::<Test>::set_block_number(8);
+			assert_ok!(KittyModule::bid(Origin::signed(3), 0, 20));
+			assert_eq!(last_event(), TestEvent::kitties(RawEvent::AuctionExtended(0, 14)));
+			assert_eq!(KittyModule::auction_of(0).unwrap().end_block, 14);
+
+			system::Module::<Test>::set_block_number(11);
+			assert_noop!(KittyModule::close_auction(Origin::signed(2), 0), "Auction has not ended");
+			system::Module::<Test>::set_block_number(14);
+			assert_ok!(KittyModule::close_auction(Origin::signed(2), 0));
+			assert_eq!(KittyModule::kitty_owner(0), Some(3));
+		});
+	}
+
+	#[test]
+	fn total_reserved_tracks_bids_and_deposits() {
+		with_externalities(&mut new_test_ext_with_kitties(vec![(1, [0x10; 16], 0), (1, [0x20; 16], 0)]), || {
+			system::Module::<Test>::set_block_number(1);
+			assert_ok!(KittyModule::create_auction(Origin::signed(1), 0, 10, 5));
+			assert_ok!(KittyModule::create_auction(Origin::signed(1), 1, 10, 5));
+			assert_eq!(KittyModule::total_reserved(), 0);
+
+			assert_ok!(KittyModule::bid(Origin::signed(2), 0, 20));
+			assert_ok!(KittyModule::bid(Origin::signed(3), 1, 30));
+			assert_eq!(KittyModule::total_reserved(), 50);
+
+			// Outbidding only counts the new bid
+			assert_ok!(KittyModule::bid(Origin::signed(3), 0, 40));
+			assert_eq!(KittyModule::total_reserved(), 70);
+
+			system::Module::<Test>::set_block_number(6);
+			assert_ok!(KittyModule::close_auction(Origin::signed(2), 0));
+			assert_eq!(KittyModule::total_reserved(), 30);
+
+			CREATION_DEPOSIT.with(|v| *v.borrow_mut() = 10);
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+			assert_eq!(KittyModule::total_reserved(), 40);
+			assert_ok!(KittyModule::burn(Origin::signed(1), 2));
+			assert_eq!(KittyModule::total_reserved(), 30);
+		});
+	}
+
+	#[test]
+	fn transfers_out_are_rate_limited() {
+		let kitties = vec![(1, [0x10; 16], 0), (1, [0x20; 16], 0), (1, [0x30; 16], 0)];
+		with_externalities(&mut new_test_ext_with_kitties(kitties), || {
+			MAX_TRANSFERS_PER_WINDOW.with(|v| *v.borrow_mut() = 2);
+			system::Module::<Test>::set_block_number(1);
+
+			assert_ok!(KittyModule::transfer_kitty(Origin::signed(1), 2, 0));
+			assert_ok!(KittyModule::safe_transfer(Origin::signed(1), 2, 1));
+			assert_noop!(KittyModule::transfer_kitty(Origin::signed(1), 2, 2), "Transfer rate limited");
+			// The recipient's own rate isn't affected
+			assert_ok!(KittyModule::transfer_kitty(Origin::signed(2), 3, 0));
+
+			system::Module::<Test>::set_block_number(11);
+			assert_ok!(KittyModule::transfer_kitty(Origin::signed(1), 2, 2));
+			assert_eq!(KittyModule::transfer_count(&1), (11, 1));
+		});
+	}
+
+	#[test]
+	fn dna_distance_counts_differing_bits() {
+		let mut dna = [0u8; 16];
+		dna[0] = 0b1011;
+		dna[15] = 0xff;
+		assert_eq!(dna_distance(&dna, &dna), 0);
+		assert_eq!(dna_distance(&dna, &[0; 16]), 11);
+		assert_eq!(dna_distance(&[0; 16], &[0xff; 16]), 128);
+
+		with_externalities(&mut new_test_ext_with_kitties(vec![(1, dna, 0), (2, [0; 16], 0)]), || {
+			assert_eq!(KittyModule::dna_distance_between(0, 1), Some(11));
+			assert_eq!(KittyModule::dna_distance_between(0, 2), None);
+		});
+	}
+
+	#[test]
+	fn transfer_clears_listings() {
+		with_externalities(&mut new_test_ext_with_kitties(vec![(1, [0x10; 16], 0)]), || {
+			assert_ok!(KittyModule::set_price(Origin::signed(1), 0, Some(10)));
+			assert_ok!(KittyModule::offer_sire(Origin::signed(1), 0));
+			assert_noop!(KittyModule::create_auction(Origin::signed(1), 0, 10, 5), "Kitty is listed for sale");
+
+			assert_ok!(KittyModule::transfer_kitty(Origin::signed(1), 2, 0));
+
+			assert_eq!(last_event(), TestEvent::kitties(RawEvent::Unlisted(0)));
+			assert_eq!(KittyModule::kitty_price(0), None);
+			assert!(!KittyModule::is_sire_offered(0));
+			assert_eq!(KittyModule::account_summary(&2).listed, vec![]);
+		});
+	}
+
+	#[test]
+	fn render_traits_follow_fixed_layout() {
+		let dna = [0x21, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15];
+		assert_eq!(Kitty(dna).render_traits(), vec![1, 1, 2, 3, 4, 5, 6, 7]);
+
+		with_externalities(&mut new_test_ext_with_kitties(vec![(1, dna, 0)]), || {
+			assert_eq!(KittyModule::kitties_render_traits(0), Some(vec![1, 1, 2, 3, 4, 5, 6, 7]));
+			assert_eq!(KittyModule::kitties_render_traits(1), None);
+		});
+	}
+
+	#[test]
+	fn batch_create_respects_max_batch_size() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_noop!(KittyModule::batch_create(Origin::signed(1), 4), "Batch too large");
+			assert_ok!(KittyModule::batch_create(Origin::signed(1), 3));
+			assert_eq!(KittyModule::owned_kitties_count(&1), 3);
+		});
+	}
+
+	#[test]
+	fn batch_transfer_respects_max_batch_size() {
+		let kitties = vec![(1, [0x10; 16], 0), (1, [0x20; 16], 0), (1, [0x30; 16], 0), (1, [0x40; 16], 0)];
+		with_externalities(&mut new_test_ext_with_kitties(kitties), || {
+			assert_noop!(KittyModule::batch_transfer(Origin::signed(1), 2, vec![0, 1, 2, 3]), "Batch too large");
+			assert_noop!(KittyModule::batch_transfer(Origin::signed(1), 2, vec![0, 1, 1]), "Duplicate kitty in batch");
+
+			assert_ok!(KittyModule::batch_transfer(Origin::signed(1), 2, vec![0, 1, 2]));
+			assert_eq!(KittyModule::kitties_of(&2), vec![0, 1, 2]);
+			assert_eq!(KittyModule::kitties_of(&1), vec![3]);
+		});
+	}
+
+	#[test]
+	fn created_with_dna_event_matches_storage() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+			let dna = KittyModule::kitties(0).unwrap().0;
+			assert_eq!(last_event(), TestEvent::kitties(RawEvent::CreatedWithDna(1, 0, dna)));
+
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+			assert_ok!(KittyModule::breed(Origin::signed(1), 0, 1));
+			let dna = KittyModule::kitties(2).unwrap().0;
+			assert!(system::Module::<Test>::events().iter().any(|record|
+				record.event == TestEvent::kitties(RawEvent::CreatedWithDna(1, 2, dna))
+			));
+		});
+	}
+
+	#[test]
+	fn consign_transfers_and_lists() {
+		with_externalities(&mut new_test_ext_with_kitties(vec![(1, [0x10; 16], 0)]), || {
+			assert_noop!(KittyModule::consign(Origin::signed(1), 2, 0, 50), "Consignment not allowed");
+
+			assert_ok!(KittyModule::allow_consignment(Origin::signed(2), 1, true));
+			assert_noop!(KittyModule::consign(Origin::signed(1), 2, 0, 501), "Price too high");
+			assert_ok!(KittyModule::consign(Origin::signed(1), 2, 0, 50));
+
+			assert_eq!(KittyModule::kitty_owner(0), Some(2));
+			assert_eq!(KittyModule::kitty_price(0), Some(50));
+			assert_eq!(last_event(), TestEvent::kitties(RawEvent::PriceSet(2, 0, 50)));
+		});
+	}
+
+	#[test]
+	fn first_kitty_id_matches_genesis() {
+		with_externalities(&mut new_test_ext_with_kitties(vec![(1, [0x10; 16], 0)]), || {
+			let first_id = KittyModule::first_kitty_id();
+			assert_eq!(KittyModule::kitty_owner(first_id), Some(1));
+			assert_eq!(KittyModule::kitty_index_info(), KittyIndexInfo { first_id, bits: 32 });
+		});
+	}
+
+	#[test]
+	fn batch_breed_is_all_or_nothing() {
+		let kitties = vec![(1, [0x10; 16], 0), (1, [0x21; 16], 0), (1, [0x30; 16], 0), (1, [0x41; 16], 0)];
+		with_externalities(&mut new_test_ext_with_kitties(kitties), || {
+			assert_noop!(
+				KittyModule::batch_breed(Origin::signed(1), vec![(0, 1), (2, 3), (0, 3)]),
+				"Kitty would be on breeding cooldown"
+			);
+			assert_eq!(KittyModule::kitties_count(), 4);
+			assert_eq!(KittyModule::cooldown_end_of(0), 0);
+
+			assert_ok!(KittyModule::batch_breed(Origin::signed(1), vec![(0, 1), (2, 3)]));
+			assert_eq!(KittyModule::kitties_count(), 6);
+			let bred: Vec<_> = system::Module::<Test>::events().into_iter()
+				.filter_map(|record| match record.event {
+					TestEvent::kitties(RawEvent::Bred(owner, parent_1, parent_2, child, _, _)) =>
+						Some((owner, parent_1, parent_2, child)),
+					_ => None,
+				})
+				.collect();
+			assert_eq!(bred, vec![(1, 0, 1, 4), (1, 2, 3, 5)]);
+		});
+	}
+
+	#[test]
+	fn pedigree_score_decays_per_generation() {
+		let dna = |id: u8, rare: usize| {
+			let mut dna = [id; 16];
+			for byte in dna.iter_mut().take(rare) {
+				*byte = 0xff;
+			}
+			dna
+		};
+		let kitties = vec![(1, dna(0, 1), 0), (1, dna(1, 2), 0), (1, dna(2, 0), 1), (1, dna(3, 3), 0), (1, dna(4, 1), 2)];
+		with_externalities(&mut new_test_ext_with_kitties(kitties), || {
+			<KittyParents<Test>>::insert(2, (0, 1));
+			<KittyParents<Test>>::insert(4, (2, 3));
+
+			assert_eq!(KittyModule::pedigree_score(0), Some(16));
+			// 16 * 1 + 8 * (0 + 3) + 4 * (1 + 2)
+			assert_eq!(KittyModule::pedigree_score(4), Some(52));
+			assert_eq!(KittyModule::pedigree_score(5), None);
+		});
+	}
+
+	#[test]
+	fn transfer_keeps_cooldown_by_default() {
+		with_externalities(&mut new_test_ext_with_kitties(vec![(1, [0x10; 16], 0), (1, [0x21; 16], 0)]), || {
+			system::Module::<Test>::set_block_number(1);
+			assert_ok!(KittyModule::breed(Origin::signed(1), 0, 1));
+			assert_ok!(KittyModule::transfer_kitty(Origin::signed(1), 2, 0));
+
+			assert_eq!(KittyModule::cooldown_end_of(0), 11);
+		});
+	}
+
+	#[test]
+	fn transfer_resets_cooldown_when_enabled() {
+		with_externalities(&mut new_test_ext_with_kitties(vec![(1, [0x10; 16], 0), (1, [0x21; 16], 0)]), || {
+			RESET_COOLDOWN_ON_TRANSFER.with(|v| *v.borrow_mut() = true);
+			system::Module::<Test>::set_block_number(1);
+			assert_ok!(KittyModule::breed(Origin::signed(1), 0, 1));
+			assert_ok!(KittyModule::transfer_kitty(Origin::signed(1), 2, 0));
+
+			assert_eq!(KittyModule::cooldown_end_of(0), 0);
+			assert_eq!(KittyModule::cooldown_end_of(1), 11);
+		});
+	}
+
+	#[test]
+	fn try_state_detects_corruption() {
+		with_externalities(&mut new_test_ext_with_kitties(vec![(1, [0x10; 16], 0), (2, [0x20; 16], 0)]), || {
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+			assert_ok!(KittyModule::transfer_kitty(Origin::signed(1), 2, 0));
+			assert_ok!(KittyModule::burn(Origin::signed(2), 1));
+			assert_ok!(KittyModule::try_state());
+
+			<DnaExists<Test>>::remove([0x10; 16]);
+			assert_eq!(KittyModule::try_state(), Err("DnaExists doesn't match kitty"));
+		});
+	}
+
+	#[test]
+	fn bid_must_meet_increment() {
+		with_externalities(&mut new_test_ext_with_kitties(vec![(1, [0x10; 16], 0)]), || {
+			system::Module::<Test>::set_block_number(1);
+			assert_ok!(KittyModule::create_auction(Origin::signed(1), 0, 10, 5));
+			assert_ok!(KittyModule::bid(Origin::signed(2), 0, 10));
+
+			assert_noop!(KittyModule::bid(Origin::signed(3), 0, 11), "Bid increment too small");
+			assert_ok!(KittyModule::bid(Origin::signed(3), 0, 15));
+
+			assert_eq!(Balances::reserved_balance(&2), 0);
+			assert_eq!(Balances::free_balance(&2), 1000);
+			assert_eq!(Balances::reserved_balance(&3), 15);
+		});
+	}
+
+	#[test]
+	fn pausing_minting_keeps_transfers_live() {
+		with_externalities(&mut new_test_ext_with_kitties(vec![(1, [0x10; 16], 0)]), || {
+			assert_noop!(KittyModule::set_paused_ops(Origin::signed(1), PAUSE_MINT), "bad origin: expected to be a root origin");
+			assert_ok!(KittyModule::set_paused_ops(Origin::ROOT, PAUSE_MINT));
+
+			assert_noop!(KittyModule::create(Origin::signed(1)), "Operation is paused");
+			assert_ok!(KittyModule::transfer_kitty(Origin::signed(1), 2, 0));
+
+			assert_ok!(KittyModule::set_paused_ops(Origin::ROOT, PAUSE_TRANSFER));
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+			assert_noop!(KittyModule::transfer_kitty(Origin::signed(2), 1, 0), "Operation is paused");
+		});
+	}
+
+	#[test]
+	fn names_can_repeat_by_default() {
+		with_externalities(&mut new_test_ext_with_kitties(vec![(1, [0x10; 16], 0), (1, [0x20; 16], 0)]), || {
+			assert_ok!(KittyModule::set_name(Origin::signed(1), 0, b"Tom".to_vec()));
+			assert_ok!(KittyModule::set_name(Origin::signed(1), 1, b"Tom".to_vec()));
+			assert_eq!(KittyModule::name_of(1), b"Tom".to_vec());
+			assert_noop!(KittyModule::set_name(Origin::signed(2), 0, b"Jerry".to_vec()), "Not owner of kitty");
+		});
+	}
+
+	#[test]
+	fn unique_names_are_taken_and_released() {
+		with_externalities(&mut new_test_ext_with_kitties(vec![(1, [0x10; 16], 0), (1, [0x20; 16], 0)]), || {
+			UNIQUE_NAMES.with(|v| *v.borrow_mut() = true);
+
+			assert_ok!(KittyModule::set_name(Origin::signed(1), 0, b"Tom".to_vec()));
+			assert_eq!(KittyModule::name_owner(b"Tom".to_vec()), Some(0));
+			assert_noop!(KittyModule::set_name(Origin::signed(1), 1, b"Tom".to_vec()), "Name taken");
+			// Setting the same name again is fine
+			assert_ok!(KittyModule::set_name(Origin::signed(1), 0, b"Tom".to_vec()));
+
+			// Renaming releases the old name
+			assert_ok!(KittyModule::set_name(Origin::signed(1), 0, b"Tommy".to_vec()));
+			assert_eq!(KittyModule::name_owner(b"Tom".to_vec()), None);
+			assert_ok!(KittyModule::set_name(Origin::signed(1), 1, b"Tom".to_vec()));
+
+			// Burning releases the name too
+			assert_ok!(KittyModule::burn(Origin::signed(1), 0));
+			assert_eq!(KittyModule::name_owner(b"Tommy".to_vec()), None);
+			assert_eq!(KittyModule::name_of(0), Vec::<u8>::new());
+		});
+	}
+
+	#[test]
+	fn scheduled_breed_runs_at_target_block() {
+		with_externalities(&mut new_test_ext_with_kitties(vec![(1, [0x10; 16], 0), (1, [0x21; 16], 0)]), || {
+			system::Module::<Test>::set_block_number(1);
+			assert_noop!(KittyModule::schedule_breed(Origin::signed(2), 0, 1, 5), "Not owner of kitty_id_1");
+			assert_ok!(KittyModule::schedule_breed(Origin::signed(1), 0, 1, 5));
+
+			KittyModule::on_initialize(4);
+			assert_eq!(KittyModule::kitties_count(), 2);
+
+			system::Module::<Test>::set_block_number(5);
+			KittyModule::on_initialize(5);
+			assert_eq!(KittyModule::kitties_count(), 3);
+			assert_eq!(KittyModule::parents_of(2), Some((0, 1)));
+			assert!(KittyModule::scheduled_breeds(5).is_empty());
+		});
+	}
+
+	#[test]
+	fn scheduled_breed_can_be_cancelled() {
+		with_externalities(&mut new_test_ext_with_kitties(vec![(1, [0x10; 16], 0), (1, [0x21; 16], 0)]), || {
+			system::Module::<Test>::set_block_number(1);
+			assert_ok!(KittyModule::schedule_breed(Origin::signed(1), 0, 1, 5));
+			assert_noop!(KittyModule::cancel_scheduled_breed(Origin::signed(2), 0, 1, 5), "Breed is not scheduled");
+			assert_ok!(KittyModule::cancel_scheduled_breed(Origin::signed(1), 0, 1, 5));
+
+			system::Module::<Test>::set_block_number(5);
+			KittyModule::on_initialize(5);
+			assert_eq!(KittyModule::kitties_count(), 2);
+		});
+	}
+
+	#[test]
+	fn scheduled_breed_is_checked_again_when_run() {
+		with_externalities(&mut new_test_ext_with_kitties(vec![(1, [0x10; 16], 0), (1, [0x21; 16], 0)]), || {
+			system::Module::<Test>::set_block_number(1);
+			assert_ok!(KittyModule::schedule_breed(Origin::signed(1), 0, 1, 5));
+			assert_ok!(KittyModule::transfer_kitty(Origin::signed(1), 2, 1));
+
+			system::Module::<Test>::set_block_number(5);
+			KittyModule::on_initialize(5);
+			assert_eq!(KittyModule::kitties_count(), 2);
+			assert_eq!(last_event(), TestEvent::kitties(RawEvent::ScheduledBreedFailed(1, 0, 1)));
+		});
+	}
+
+	#[test]
+	fn redeem_voucher_mints_once() {
+		with_externalities(&mut new_test_ext(), || {
+			let dna = [0x42; 16];
+			let voucher = TestSignature(8, (1u64, dna).encode());
+
+			assert_noop!(
+				KittyModule::redeem_voucher(Origin::signed(1), dna, TestSignature(7, (1u64, dna).encode())),
+				"Invalid voucher signature"
+			);
+			// The voucher is bound to the account it was signed for
+			assert_noop!(KittyModule::redeem_voucher(Origin::signed(2), dna, voucher.clone()), "Invalid voucher signature");
+
+			assert_ok!(KittyModule::redeem_voucher(Origin::signed(1), dna, voucher.clone()));
+			assert_eq!(KittyModule::kitties(0), Some(Kitty(dna)));
+			assert_eq!(KittyModule::kitty_owner(0), Some(1));
+
+			assert_ok!(KittyModule::burn(Origin::signed(1), 0));
+			assert_noop!(KittyModule::redeem_voucher(Origin::signed(1), dna, voucher), "Voucher already redeemed");
+		});
+	}
+
+	#[test]
+	fn balanced_gender_favours_minority() {
+		assert_eq!(balanced_gender((10, 0), 0xff), Gender::Female);
+		assert_eq!(balanced_gender((0, 10), 0), Gender::Male);
+
+		let females = (0..=255u8).filter(|e| balanced_gender((90, 10), *e) == Gender::Female).count();
+		assert!(females > 200);
+		let females = (0..=255u8).filter(|e| balanced_gender((50, 50), *e) == Gender::Female).count();
+		assert_eq!(females, 128);
+	}
+
+	#[test]
+	fn skewed_population_breeds_minority_gender() {
+		let males = vec![(1, [0; 16], 0), (1, [2; 16], 0), (1, [4; 16], 0), (1, [6; 16], 0)];
+		with_externalities(&mut new_test_ext_with_kitties(males), || {
+			BALANCED_GENDERS.with(|v| *v.borrow_mut() = true);
+			assert_eq!(KittyModule::gender_counts(), (4, 0));
+
+			assert_ok!(KittyModule::breed(Origin::signed(1), 0, 1));
+			assert_eq!(KittyModule::kitties(4).unwrap().gender(), Gender::Female);
+			assert_eq!(KittyModule::gender_counts(), (4, 1));
+
+			assert_ok!(KittyModule::burn(Origin::signed(1), 4));
+			assert_eq!(KittyModule::gender_counts(), (4, 0));
+		});
+	}
+
+	#[test]
+	fn dna_index_migration_flags_duplicates() {
+		let kitties = vec![(1, [0x10; 16], 0), (2, [0x20; 16], 0), (2, [0x30; 16], 0)];
+		with_externalities(&mut new_test_ext_with_kitties(kitties), || {
+			// Kitties stored before the DNA index existed
+			<DnaIndexMigrated>::put(false);
+			<Kitties<Test>>::insert(2, Kitty([0x10; 16]));
+			<DnaExists<Test>>::remove([0x10; 16]);
+			<DnaExists<Test>>::remove([0x30; 16]);
+
+			KittyModule::on_initialize(1);
+			assert!(KittyModule::dna_index_migrated());
+			assert_eq!(KittyModule::duplicate_dna_kitties(), vec![2]);
+			assert_eq!(KittyModule::kitties_id_by_dna([0x10; 16]), Some(0));
+			assert_eq!(KittyModule::kitties_id_by_dna([0x20; 16]), Some(1));
+			// The duplicate is kept
+			assert_eq!(KittyModule::kitty_owner(2), Some(2));
+			assert_ok!(KittyModule::try_state());
+
+			assert_ok!(KittyModule::burn(Origin::signed(2), 2));
+			assert_eq!(KittyModule::kitties_id_by_dna([0x10; 16]), Some(0));
+		});
+	}
+
+	#[test]
+	fn dna_index_migration_runs_in_chunks() {
+		let dna = |i: u32| {
+			let mut dna = [0u8; 16];
+			dna[..4].copy_from_slice(&i.to_le_bytes());
+			dna
+		};
+		let count = MAX_DNA_MIGRATION_PER_BLOCK + 20;
+		let kitties = (0..count).map(|i| (1, dna(i), 0)).collect();
+		with_externalities(&mut new_test_ext_with_kitties(kitties), || {
+			<DnaIndexMigrated>::put(false);
+			for i in 0..count {
+				<DnaExists<Test>>::remove(dna(i));
+			}
+
+			KittyModule::on_initialize(1);
+			assert!(!KittyModule::dna_index_migrated());
+			assert_eq!(KittyModule::dna_index_cursor(), MAX_DNA_MIGRATION_PER_BLOCK);
+			assert_eq!(KittyModule::kitties_id_by_dna(dna(MAX_DNA_MIGRATION_PER_BLOCK - 1)), Some(MAX_DNA_MIGRATION_PER_BLOCK - 1));
+			assert_eq!(KittyModule::kitties_id_by_dna(dna(MAX_DNA_MIGRATION_PER_BLOCK)), None);
+
+			KittyModule::on_initialize(2);
+			assert!(KittyModule::dna_index_migrated());
+			assert_eq!(KittyModule::dna_index_cursor(), 0);
+			assert_eq!(KittyModule::kitties_id_by_dna(dna(count - 1)), Some(count - 1));
+			assert_ok!(KittyModule::try_state());
+		});
+	}
+
+	#[test]
+	fn genesis_chain_skips_dna_index_migration() {
+		with_externalities(&mut new_test_ext_with_kitties(vec![(1, [0x10; 16], 0)]), || {
+			assert!(KittyModule::dna_index_migrated());
+			// A rescan would index the kitty again
+			<DnaExists<Test>>::remove([0x10; 16]);
+			KittyModule::on_initialize(1);
+			assert_eq!(KittyModule::kitties_id_by_dna([0x10; 16]), None);
+		});
+	}
+
+	#[test]
+	fn incubating_kitty_belongs_to_hatcher() {
+		with_externalities(&mut new_test_ext(), || {
+			INCUBATION.with(|v| *v.borrow_mut() = true);
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+
+			assert!(KittyModule::is_incubating(0));
+			assert!(KittyModule::kitties(0).is_some());
+			assert_eq!(KittyModule::kitty_owner(0), None);
+			assert_eq!(KittyModule::owned_kitties_count(1), 0);
+			assert_noop!(KittyModule::transfer_kitty(Origin::signed(1), 2, 0), "Not owner of kitty");
+			assert_ok!(KittyModule::try_state());
+
+			Balances::make_free_balance_be(&4, 10);
+			assert_noop!(KittyModule::hatch(Origin::signed(4), 0), "Cannot pay hatch fee");
+			assert_ok!(KittyModule::hatch(Origin::signed(2), 0));
+			assert_eq!(last_event(), TestEvent::kitties(RawEvent::Hatched(2, 0)));
+			assert!(!KittyModule::is_incubating(0));
+			assert_eq!(KittyModule::kitty_owner(0), Some(2));
+			assert_eq!(KittyModule::kitties_of(&2), vec![0]);
+			assert_eq!(Balances::free_balance(2), 980);
+			assert_eq!(Balances::free_balance(9), 20);
+			assert_ok!(KittyModule::try_state());
+
+			assert_noop!(KittyModule::hatch(Origin::signed(3), 0), "Kitty is not incubating");
+		});
+	}
+
+	#[test]
+	fn approve_many_is_all_or_nothing() {
+		let kitties = vec![(1, [0x10; 16], 0), (1, [0x20; 16], 0), (2, [0x30; 16], 0), (1, [0x40; 16], 0)];
+		with_externalities(&mut new_test_ext_with_kitties(kitties), || {
+			assert_noop!(KittyModule::approve_many(Origin::signed(1), 3, vec![0, 1, 3, 0]), "Batch too large");
+			assert_noop!(KittyModule::approve_many(Origin::signed(1), 3, vec![0, 2, 1]), "Not owner of kitty");
+			assert_eq!(KittyModule::approved_of(0), None);
+
+			assert_ok!(KittyModule::approve_many(Origin::signed(1), 3, vec![0, 1]));
+			assert_ok!(KittyModule::transfer_from(Origin::signed(3), 1, 3, 0));
+			assert_ok!(KittyModule::transfer_from(Origin::signed(3), 1, 4, 1));
+			assert_eq!(KittyModule::kitty_owner(0), Some(3));
+			assert_eq!(KittyModule::kitty_owner(1), Some(4));
+			assert_noop!(KittyModule::transfer_from(Origin::signed(3), 1, 3, 3), "Not approved to transfer kitty");
+		});
+	}
+
+	#[test]
+	fn locked_traits_come_from_matron() {
+		let matron = [0x5a; 16];
+		let kitties = vec![(1, matron, 0), (1, [0xa5; 16], 0)];
+		with_externalities(&mut new_test_ext_with_kitties(kitties), || {
+			let mut mask = [0; 16];
+			mask[3] = 0xff;
+			mask[15] = 0x0f;
+			LOCKED_TRAIT_MASK.with(|v| *v.borrow_mut() = mask);
+
+			for block in 1..6 {
+				system::Module::<Test>::set_block_number(block * 10);
+				assert_ok!(KittyModule::breed(Origin::signed(1), 0, 1));
+				let dna = KittyModule::kitties(block as u32 + 1).unwrap().0;
+				assert_eq!(dna[3], matron[3]);
+				assert_eq!(dna[15] & 0x0f, matron[15] & 0x0f);
+			}
+		});
+	}
+
+	#[test]
+	fn breeding_compatibility_reports_cooldown() {
+		let kitties = vec![(1, [0x10; 16], 0), (1, [0x21; 16], 0), (1, [0x30; 16], 0), (1, [0x41; 16], 0)];
+		with_externalities(&mut new_test_ext_with_kitties(kitties), || {
+			assert_ok!(KittyModule::breed(Origin::signed(1), 0, 1));
+			system::Module::<Test>::set_block_number(4);
+
+			assert_eq!(KittyModule::breeding_compatibility(0, 1), CompatibilityReport {
+				can_breed: false,
+				reason: Some(Incompatibility::OnCooldown),
+				cooldown_remaining: Some(6),
+				dna_distance: dna_distance(&[0x10; 16], &[0x21; 16]),
+			});
+			assert_eq!(KittyModule::breeding_compatibility(2, 3), CompatibilityReport {
+				can_breed: true,
+				reason: None,
+				cooldown_remaining: None,
+				dna_distance: dna_distance(&[0x30; 16], &[0x41; 16]),
+			});
+			assert_eq!(KittyModule::breeding_compatibility(2, 2).reason, Some(Incompatibility::SameKitty));
+			assert_eq!(KittyModule::breeding_compatibility(2, 9).reason, Some(Incompatibility::InvalidKitty));
+		});
+	}
+
+	#[test]
+	fn listing_price_is_capped() {
+		with_externalities(&mut new_test_ext_with_kitties(vec![(1, [0x10; 16], 0), (1, [0x20; 16], 0)]), || {
+			assert_noop!(KittyModule::set_price(Origin::signed(1), 0, Some(501)), "Price too high");
+			assert_noop!(KittyModule::create_auction(Origin::signed(1), 1, 501, 10), "Price too high");
+
+			assert_ok!(KittyModule::set_price(Origin::signed(1), 0, Some(500)));
+			assert_eq!(KittyModule::kitty_price(0), Some(500));
+			assert_ok!(KittyModule::create_auction(Origin::signed(1), 1, 500, 10));
+		});
+	}
+
+	#[test]
+	fn rewriting_generation_moves_bucket_and_emits_event() {
+		with_externalities(&mut new_test_ext_with_kitties(vec![(1, [0x10; 16], 2), (1, [0x20; 16], 2)]), || {
+			assert!(system::Module::<Test>::events().is_empty());
+			assert_eq!(KittyModule::kitties_by_generation(2), vec![0, 1]);
+
+			assert_noop!(KittyModule::force_set_generation(Origin::signed(1), 0, 5), "bad origin: expected to be a root origin");
+			assert_noop!(KittyModule::force_set_generation(Origin::ROOT, 7, 5), "Invalid kitty_id");
+			assert_noop!(
+				KittyModule::force_set_generation(Origin::ROOT, 0, MaxGeneration::get() + 1),
+				"Max generation reached"
+			);
+
+			assert_ok!(KittyModule::force_set_generation(Origin::ROOT, 0, 2));
+			assert!(system::Module::<Test>::events().is_empty());
+
+			assert_ok!(KittyModule::force_set_generation(Origin::ROOT, 0, 5));
+			assert_eq!(KittyModule::generation_of(0), 5);
+			assert_eq!(KittyModule::kitties_by_generation(2), vec![1]);
+			assert_eq!(KittyModule::kitties_by_generation(5), vec![0]);
+			assert_eq!(last_event(), TestEvent::kitties(RawEvent::GenerationChanged(0, 2, 5)));
+
+			assert_ok!(KittyModule::burn(Origin::signed(1), 0));
+			assert!(KittyModule::kitties_by_generation(5).is_empty());
+		});
+	}
+
+	#[test]
+	fn new_kitties_are_indexed_by_generation() {
+		with_externalities(&mut new_test_ext_with_kitties(vec![(1, [0x10; 16], 0), (1, [0x21; 16], 0)]), || {
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+			assert_ok!(KittyModule::breed(Origin::signed(1), 0, 1));
+			assert_eq!(KittyModule::kitties_by_generation(0), vec![0, 1, 2]);
+			assert_eq!(KittyModule::kitties_by_generation(1), vec![3]);
+			assert!(system::Module::<Test>::events().iter().all(|record| match record.event {
+				TestEvent::kitties(RawEvent::GenerationChanged(..)) => false,
+				_ => true,
+			}));
+		});
+	}
+
+	#[test]
+	fn deposit_stays_with_creator() {
+		with_externalities(&mut new_test_ext(), || {
+			CREATION_DEPOSIT.with(|v| *v.borrow_mut() = 10);
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+			assert_eq!(Balances::reserved_balance(1), 10);
+			assert_eq!(KittyModule::deposit_of(0), Some((1, 10)));
+
+			assert_ok!(KittyModule::transfer_kitty(Origin::signed(1), 2, 0));
+			assert_eq!(Balances::reserved_balance(1), 10);
+			assert_eq!(Balances::reserved_balance(2), 0);
+
+			assert_ok!(KittyModule::burn(Origin::signed(2), 0));
+			assert_eq!(Balances::reserved_balance(1), 0);
+			assert_eq!(KittyModule::deposit_of(0), None);
+		});
+	}
+
+	#[test]
+	fn deposit_follows_kitty() {
+		with_externalities(&mut new_test_ext(), || {
+			CREATION_DEPOSIT.with(|v| *v.borrow_mut() = 10);
+			DEPOSIT_FOLLOWS_KITTY.with(|v| *v.borrow_mut() = true);
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+
+			assert_ok!(KittyModule::transfer_kitty(Origin::signed(1), 2, 0));
+			assert_eq!(Balances::reserved_balance(1), 0);
+			assert_eq!(Balances::free_balance(1), 1000);
+			assert_eq!(Balances::reserved_balance(2), 10);
+			assert_eq!(KittyModule::deposit_of(0), Some((2, 10)));
+
+			Balances::make_free_balance_be(&4, 5);
+			assert_noop!(KittyModule::transfer_kitty(Origin::signed(2), 4, 0), "Cannot reserve kitty deposit");
+
+			assert_ok!(KittyModule::burn(Origin::signed(2), 0));
+			assert_eq!(Balances::reserved_balance(2), 0);
+			assert_eq!(Balances::free_balance(2), 1000);
+		});
+	}
+
+	#[test]
+	fn renames_are_recorded() {
+		with_externalities(&mut new_test_ext_with_kitties(vec![(1, [0x10; 16], 0)]), || {
+			system::Module::<Test>::set_block_number(1);
+			assert_ok!(KittyModule::set_name(Origin::signed(1), 0, b"Tom".to_vec()));
+			system::Module::<Test>::set_block_number(5);
+			assert_ok!(KittyModule::set_name(Origin::signed(1), 0, b"Tommy".to_vec()));
+			system::Module::<Test>::set_block_number(9);
+			assert_ok!(KittyModule::set_name(Origin::signed(1), 0, b"Tim".to_vec()));
+
+			assert_eq!(KittyModule::name_history_of(0), vec![
+				(b"Tom".to_vec(), 1),
+				(b"Tommy".to_vec(), 5),
+				(b"Tim".to_vec(), 9),
+			]);
+
+			for _ in 0..MAX_NAME_HISTORY {
+				assert_ok!(KittyModule::set_name(Origin::signed(1), 0, b"Tom".to_vec()));
+			}
+			let history = KittyModule::name_history_of(0);
+			assert_eq!(history.len(), MAX_NAME_HISTORY);
+			assert!(history.iter().all(|(name, _)| name == b"Tom"));
+		});
+	}
+
+	#[test]
+	fn use_speedup_halves_cooldown() {
+		with_externalities(&mut new_test_ext_with_kitties(vec![(1, [0x10; 16], 0), (1, [0x21; 16], 0)]), || {
+			assert_noop!(KittyModule::use_speedup(Origin::signed(1), 0), "Kitty is not on cooldown");
+			assert_ok!(KittyModule::breed(Origin::signed(1), 0, 1));
+			assert_eq!(KittyModule::cooldown_end_of(0), 10);
+			assert_eq!(KittyModule::tokens(1), 1);
+
+			// The breeding token is spent first
+			assert_ok!(KittyModule::use_speedup(Origin::signed(1), 0));
+			assert_eq!(KittyModule::cooldown_end_of(0), 5);
+			assert_eq!(KittyModule::tokens(1), 0);
+
+			system::Module::<Test>::set_block_number(4);
+			assert_ok!(KittyModule::use_speedup(Origin::signed(1), 0));
+			assert_eq!(KittyModule::cooldown_end_of(0), 4);
+			assert_eq!(Balances::free_balance(1), 985);
+			assert_noop!(KittyModule::use_speedup(Origin::signed(1), 0), "Kitty is not on cooldown");
+		});
+	}
+
+	#[test]
+	fn ready_to_breed_skips_unavailable_kitties() {
+		let kitties = vec![
+			(1, [0x10; 16], 0), (1, [0x21; 16], 0), (1, [0x30; 16], 0),
+			(1, [0x41; 16], 0), (1, [0x50; 16], 0), (2, [0x60; 16], 0),
+		];
+		with_externalities(&mut new_test_ext_with_kitties(kitties), || {
+			assert_ok!(KittyModule::breed(Origin::signed(1), 0, 1));
+			assert_ok!(KittyModule::retire(Origin::signed(1), 2));
+			assert_ok!(KittyModule::create_auction(Origin::signed(1), 3, 10, 20));
+
+			assert_eq!(KittyModule::ready_to_breed(&1), vec![4, 6]);
+
+			system::Module::<Test>::set_block_number(10);
+			assert_eq!(KittyModule::ready_to_breed(&1), vec![0, 1, 4, 6]);
+		});
+	}
+
+	#[test]
+	fn zero_random_seed_falls_back() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_eq!(system::Module::<Test>::random_seed(), H256::default());
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+			assert_ok!(KittyModule::create(Origin::signed(2)));
+
+			let dnas: Vec<_> = (0..3).map(|id| KittyModule::kitties(id).unwrap().0).collect();
+			assert_ne!(dnas[0], dnas[1]);
+			assert_ne!(dnas[1], dnas[2]);
+			assert_ne!(dnas[0], [0; 16]);
+			assert!(system::Module::<Test>::events().iter().any(|record|
+				record.event == TestEvent::kitties(RawEvent::InsecureRandomness(0))
+			));
+		});
+	}
+
+	#[test]
+	fn fuse_burns_both_kitties() {
+		let kitties = vec![(1, [0x10; 16], 1), (1, [0x21; 16], 3), (2, [0x30; 16], 0)];
+		with_externalities(&mut new_test_ext_with_kitties(kitties), || {
+			assert_noop!(KittyModule::fuse(Origin::signed(1), 0, 2), "Not owner of kitty_id_2");
+			assert_noop!(KittyModule::fuse(Origin::signed(1), 0, 0), "Needs different kitties");
+
+			assert_ok!(KittyModule::fuse(Origin::signed(1), 0, 1));
+			assert_eq!(last_event(), TestEvent::kitties(RawEvent::Fused(1, 0, 1, 3)));
+
+			assert_eq!(KittyModule::kitties(0), None);
+			assert_eq!(KittyModule::kitties(1), None);
+			assert_eq!(KittyModule::kitty_owner(0), None);
+			assert_eq!(KittyModule::kitties_id_by_dna([0x10; 16]), None);
+
+			let dna = fuse_dna(&[0x10; 16], &[0x21; 16]);
+			assert_eq!(KittyModule::kitties(3), Some(Kitty(dna)));
+			assert_eq!(KittyModule::kitties_of(&1), vec![3]);
+			assert_eq!(KittyModule::generation_of(3), 4);
+			assert_eq!(KittyModule::parents_of(3), Some((0, 1)));
+			assert_eq!(KittyModule::kitties_count(), 4);
+			assert_ok!(KittyModule::try_state());
+		});
+	}
+
+	#[test]
+	fn kitty_profile_counts_down_cooldown() {
+		with_externalities(&mut new_test_ext_with_kitties(vec![(1, [0x10; 16], 0), (1, [0x21; 16], 0)]), || {
+			assert_eq!(KittyModule::kitty_profile(0).unwrap().cooldown_remaining, 0);
+			assert_ok!(KittyModule::breed(Origin::signed(1), 0, 1));
+			assert_eq!(KittyModule::kitty_profile(2).unwrap().cooldown_remaining, 0);
+
+			assert_eq!(KittyModule::kitty_profile(0), Some(KittyProfile {
+				kitty: Kitty([0x10; 16]),
+				owner: Some(1),
+				generation: 0,
+				name: Vec::new(),
+				price: None,
+				cooldown_remaining: 10,
+				license: 0,
+			}));
+			system::Module::<Test>::set_block_number(6);
+			assert_eq!(KittyModule::kitty_profile(0).unwrap().cooldown_remaining, 4);
+			system::Module::<Test>::set_block_number(12);
+			assert_eq!(KittyModule::kitty_profile(0).unwrap().cooldown_remaining, 0);
+			assert_eq!(KittyModule::kitty_profile(9), None);
+		});
+	}
+
+	#[test]
+	fn presale_is_allowlist_only() {
+		with_externalities(&mut new_test_ext(), || {
+			PRESALE_END.with(|v| *v.borrow_mut() = 10);
+			assert_ok!(KittyModule::set_allowance(Origin::ROOT, 1, 2));
+
+			assert_noop!(KittyModule::create(Origin::signed(2)), "Not allowlisted for presale");
+			assert_noop!(KittyModule::batch_create(Origin::signed(1), 3), "Not allowlisted for presale");
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+			assert_eq!(KittyModule::allowance(1), 0);
+			assert_noop!(KittyModule::create(Origin::signed(1)), "Not allowlisted for presale");
+
+			system::Module::<Test>::set_block_number(10);
+			assert_ok!(KittyModule::create(Origin::signed(2)));
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+			assert_eq!(KittyModule::kitty_owner(2), Some(2));
+		});
+	}
+
+	#[test]
+	fn same_species_only_rejects_cross_breeding() {
+		let kitties = vec![(1, [0x10; 16], 0), (1, [0x21; 16], 0), (1, [0xc0; 16], 0)];
+		with_externalities(&mut new_test_ext_with_kitties(kitties), || {
+			assert_eq!(KittyModule::species_of(0), Some(Species::Tabby));
+			assert_eq!(KittyModule::species_of(2), Some(Species::Sphynx));
+			assert_eq!(KittyModule::species_of(3), None);
+
+			SAME_SPECIES_ONLY.with(|v| *v.borrow_mut() = true);
+			assert_noop!(KittyModule::breed(Origin::signed(1), 0, 2), "Species mismatch");
+			assert_eq!(KittyModule::breeding_compatibility(0, 2).reason, Some(Incompatibility::SpeciesMismatch));
+			assert_ok!(KittyModule::breed(Origin::signed(1), 0, 1));
+
+			SAME_SPECIES_ONLY.with(|v| *v.borrow_mut() = false);
+			system::Module::<Test>::set_block_number(10);
+			assert_ok!(KittyModule::breed(Origin::signed(1), 0, 2));
+		});
+	}
+
+	#[test]
+	fn compact_batch_events_summarize() {
+		with_externalities(&mut new_test_ext(), || {
+			COMPACT_BATCH_EVENTS.with(|v| *v.borrow_mut() = true);
+			assert_ok!(KittyModule::batch_create(Origin::signed(1), 3));
+
+			let kitty_events: Vec<_> = system::Module::<Test>::events().into_iter()
+				.filter(|record| match record.event {
+					TestEvent::kitties(RawEvent::InsecureRandomness(_)) => false,
+					TestEvent::kitties(_) => true,
+					_ => false,
+				})
+				.collect();
+			assert_eq!(kitty_events.len(), 1);
+			assert_eq!(last_event(), TestEvent::kitties(RawEvent::BatchCreated(1, vec![0, 1, 2])));
+
+			assert_ok!(KittyModule::batch_transfer(Origin::signed(1), 2, vec![2, 0]));
+			assert_eq!(last_event(), TestEvent::kitties(RawEvent::BatchTransferred(1, 2, vec![2, 0])));
+		});
+	}
+
+	#[test]
+	fn repair_owner_count_fixes_count() {
+		let kitties = vec![(1, [0x10; 16], 0), (2, [0x20; 16], 0), (1, [0x30; 16], 0)];
+		with_externalities(&mut new_test_ext_with_kitties(kitties), || {
+			<OwnedKittiesCount<Test>>::insert(1, 5);
+			assert_eq!(KittyModule::try_state(), Err("Owned kitties counts don't match kitties"));
+
+			assert_ok!(KittyModule::repair_owner_count(Origin::ROOT, 1));
+			assert_eq!(last_event(), TestEvent::kitties(RawEvent::OwnerCountRepaired(1, 5, 2)));
+			assert_eq!(KittyModule::owned_kitties_count(1), 2);
+			assert_eq!(KittyModule::kitties_of(&1), vec![0, 2]);
+			assert_ok!(KittyModule::try_state());
+
+			assert_ok!(KittyModule::transfer_kitty(Origin::signed(1), 2, 2));
+			assert_eq!(KittyModule::kitties_of(&1), vec![0]);
+		});
+	}
+
+	#[test]
+	fn birth_certificate_is_immutable() {
+		let mut dna = [0x10; 16];
+		dna[0] = 0xab;
+		with_externalities(&mut new_test_ext_with_kitties(vec![(1, dna, 0), (1, [0x21; 16], 0)]), || {
+			system::Module::<Test>::set_block_number(3);
+			assert_ok!(KittyModule::breed(Origin::signed(1), 0, 1));
+			let child = KittyModule::birth_certificate(2).unwrap();
+			assert_eq!(child.generation, 1);
+			assert_eq!(child.parents, Some((0, 1)));
+			assert_eq!(child.birth_block, 3);
+			assert_eq!(child.creator, Some(1));
+
+			let certificate = KittyModule::birth_certificate(0).unwrap();
+			assert_eq!(certificate.dna_hex, b"ab101010101010101010101010101010".to_vec());
+			assert_ok!(KittyModule::set_name(Origin::signed(1), 0, b"Tom".to_vec()));
+			assert_ok!(KittyModule::transfer_kitty(Origin::signed(1), 2, 0));
+			assert_eq!(KittyModule::birth_certificate(0), Some(certificate));
+			assert_eq!(KittyModule::birth_certificate(9), None);
+		});
+	}
+
+	#[test]
+	fn tombstoned_kitty_keeps_lineage() {
+		let kitties = vec![(1, [0x10; 16], 0), (1, [0x21; 16], 0), (1, [0x30; 16], 0)];
+		with_externalities(&mut new_test_ext_with_kitties(kitties), || {
+			USE_TOMBSTONES.with(|v| *v.borrow_mut() = true);
+			assert_ok!(KittyModule::breed(Origin::signed(1), 0, 1));
+			assert_ok!(KittyModule::burn(Origin::signed(1), 0));
+
+			assert!(KittyModule::is_tombstoned(0));
+			assert_eq!(KittyModule::kitties(0), Some(Kitty([0x10; 16])));
+			assert_eq!(KittyModule::parents_of(3), Some((0, 1)));
+			assert_eq!(KittyModule::kitties_id_by_dna([0x10; 16]), Some(0));
+			assert_eq!(KittyModule::kitty_owner(0), None);
+			assert_eq!(KittyModule::owned_kitties_count(1), 3);
+			assert_ok!(KittyModule::try_state());
+
+			system::Module::<Test>::set_block_number(10);
+			assert_noop!(KittyModule::transfer_kitty(Origin::signed(1), 2, 0), "Not owner of kitty");
+			assert_noop!(KittyModule::breed(Origin::signed(1), 0, 2), "Not owner of kitty");
+			assert_noop!(KittyModule::burn(Origin::signed(1), 0), "Not owner of kitty");
+			assert_noop!(KittyModule::clone_kitty(Origin::ROOT, 0, 2), "Invalid source kitty");
+		});
+	}
+
+	#[test]
+	fn random_subjects_are_independent() {
+		with_externalities(&mut new_test_ext(), || {
+			let create = KittyModule::random_value(CREATE_SUBJECT, &1);
+			<Nonce>::put(0);
+			let breed = KittyModule::random_value(BREED_SUBJECT, &1);
+			assert_ne!(create, breed);
+			assert!(create.iter().zip(breed.iter()).filter(|(a, b)| a == b).count() < 4);
+
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+			assert_ok!(KittyModule::create(Origin::signed(1)));
+			let nonce = KittyModule::nonce();
+			let created = KittyModule::random_value(CREATE_SUBJECT, &1);
+			<Nonce>::put(nonce);
+			assert_ne!(KittyModule::random_value(BREED_SUBJECT, &1), created);
+		});
+	}
+
+	#[test]
+	fn set_license_charges_upgrades() {
+		with_externalities(&mut new_test_ext_with_kitties(vec![(1, [0x10; 16], 0)]), || {
+			assert_eq!(KittyModule::license_of(0), 0);
+			assert_noop!(KittyModule::set_license(Origin::signed(2), 0, 1), "Not owner of kitty");
+			assert_noop!(KittyModule::set_license(Origin::signed(1), 0, 4), "License tier too high");
+
+			assert_ok!(KittyModule::set_license(Origin::signed(1), 0, 3));
+			assert_eq!(KittyModule::license_of(0), 3);
+			assert_eq!(KittyModule::kitty_profile(0).unwrap().license, 3);
+			assert_eq!(Balances::free_balance(1), 970);
+
+			assert_ok!(KittyModule::set_license(Origin::signed(1), 0, 1));
+			assert_eq!(KittyModule::license_of(0), 1);
+			assert_eq!(Balances::free_balance(1), 970);
+		});
+	}
+
+	#[test]
+	fn operators_per_owner_are_capped() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_ok!(KittyModule::set_approval_for_all(Origin::signed(1), 2, true));
+			assert_ok!(KittyModule::set_approval_for_all(Origin::signed(1), 3, true));
+			assert_ok!(KittyModule::set_approval_for_all(Origin::signed(1), 3, true));
+			assert_eq!(KittyModule::operator_count(1), 2);
+			assert_noop!(KittyModule::set_approval_for_all(Origin::signed(1), 4, true), "Too many operators");
+
+			assert_ok!(KittyModule::set_approval_for_all(Origin::signed(1), 2, false));
+			assert_ok!(KittyModule::set_approval_for_all(Origin::signed(1), 2, false));
+			assert_eq!(KittyModule::operator_count(1), 1);
+			assert_ok!(KittyModule::set_approval_for_all(Origin::signed(1), 4, true));
+			assert!(KittyModule::is_approved_for_all(1, 4));
+		});
+	}
+
+	#[test]
+	fn dry_run_create_reports_errors() {
+		with_externalities(&mut new_test_ext_with_kitties(vec![(2, [0x10; 16], 0)]), || {
+			assert_eq!(KittyModule::dry_run_create(&1), Ok(1));
+			assert_eq!(KittyModule::dry_run_create(&4), Err("Balance too low to create kitty"));
+
+			MAX_KITTIES_PER_ACCOUNT.with(|v| *v.borrow_mut() = 1);
+			assert_eq!(KittyModule::dry_run_create(&2), Err("Too many kitties owned"));
+			assert_noop!(KittyModule::create(Origin::signed(2)), "Too many kitties owned");
+			assert_eq!(KittyModule::nonce(), 0);
+		});
+	}
+
+	#[test]
+	fn egg_is_revealed_after_delay() {
+		with_externalities(&mut new_test_ext(), || {
+			let secret = b"secret".to_vec();
+			let commitment = BlakeTwo256::hash_of(&(1u64, &secret));
+			system::Module::<Test>::set_block_number(1);
+			assert_ok!(KittyModule::commit_egg(Origin::signed(1), commitment));
+			assert_eq!(Balances::reserved_balance(1), 25);
+			assert_noop!(KittyModule::commit_egg(Origin::signed(1), commitment), "Egg already committed");
+
+			system::Module::<Test>::set_block_number(3);
+			assert_noop!(KittyModule::reveal_egg(Origin::signed(1), secret.clone()), "Egg not ready");
+
+			system::Module::<Test>::set_block_number(4);
+			assert_noop!(KittyModule::reveal_egg(Origin::signed(1), b"guess".to_vec()), "Egg commitment mismatch");
+			assert_ok!(KittyModule::reveal_egg(Origin::signed(1), secret.clone()));
+			assert_eq!(KittyModule::kitty_owner(0), Some(1));
+			assert_eq!(KittyModule::egg_of(1), None);
+			assert_eq!(Balances::reserved_balance(1), 0);
+			assert_noop!(KittyModule::reveal_egg(Origin::signed(1), secret), "No egg committed");
+		});
+	}
+
+	#[test]
+	fn late_egg_reveal_is_rejected() {
+		with_externalities(&mut new_test_ext(), || {
+			let secret = b"secret".to_vec();
+			let commitment = BlakeTwo256::hash_of(&(1u64, &secret));
+			system::Module::<Test>::set_block_number(1);
+			assert_ok!(KittyModule::commit_egg(Origin::signed(1), commitment));
+
+			system::Module::<Test>::set_block_number(10);
+			assert_noop!(KittyModule::reveal_egg(Origin::signed(1), secret.clone()), "Egg expired");
+
+			// The expired egg is replaced without reserving again
+			assert_ok!(KittyModule::commit_egg(Origin::signed(1), commitment));
+			assert_eq!(Balances::reserved_balance(1), 25);
+			system::Module::<Test>::set_block_number(13);
+			assert_ok!(KittyModule::reveal_egg(Origin::signed(1), secret));
+		});
+	}
+
+	#[test]
+	fn expired_egg_deposit_is_reclaimed() {
+		with_externalities(&mut new_test_ext(), || {
+			let commitment = BlakeTwo256::hash_of(&(1u64, b"secret".to_vec()));
+			system::Module::<Test>::set_block_number(1);
+			assert_noop!(KittyModule::reclaim_egg(Origin::signed(1)), "No egg committed");
+			assert_ok!(KittyModule::commit_egg(Origin::signed(1), commitment));
+			assert_ok!(KittyModule::commit_egg(Origin::signed(2), commitment));
+			assert_eq!(KittyModule::total_egg_deposits(), 50);
+			assert_eq!(KittyModule::total_reserved(), 50);
+
+			system::Module::<Test>::set_block_number(9);
+			assert_noop!(KittyModule::reclaim_egg(Origin::signed(1)), "Egg not expired");
+
+			system::Module::<Test>::set_block_number(10);
+			assert_ok!(KittyModule::reclaim_egg(Origin::signed(1)));
+			assert_eq!(KittyModule::egg_of(1), None);
+			assert_eq!(Balances::reserved_balance(1), 0);
+			assert_eq!(KittyModule::total_reserved(), 25);
+		});
+	}
+
+	#[test]
+	fn egg_deposit_change_keeps_committed_amount() {
+		with_externalities(&mut new_test_ext(), || {
+			let commitment = BlakeTwo256::hash_of(&(1u64, b"secret".to_vec()));
+			system::Module::<Test>::set_block_number(1);
+			assert_ok!(KittyModule::commit_egg(Origin::signed(1), commitment));
+			assert_ok!(KittyModule::commit_egg(Origin::signed(2), commitment));
+
+			// Replacing an expired egg keeps the deposit reserved for it
+			EGG_DEPOSIT.with(|v| *v.borrow_mut() = 40);
+			system::Module::<Test>::set_block_number(10);
+			assert_ok!(KittyModule::commit_egg(Origin::signed(1), commitment));
+			assert_eq!(Balances::reserved_balance(1), 25);
+			assert_eq!(KittyModule::egg_of(1), Some((commitment, 10, 25)));
+
+			EGG_DEPOSIT.with(|v| *v.borrow_mut() = 10);
+			assert_ok!(KittyModule::reclaim_egg(Origin::signed(2)));
+			assert_eq!(Balances::reserved_balance(2), 0);
+			assert_eq!(KittyModule::total_egg_deposits(), 25);
+
+			system::Module::<Test>::set_block_number(19);
+			assert_ok!(KittyModule::reclaim_egg(Origin::signed(1)));
+			assert_eq!(Balances::reserved_balance(1), 0);
+			assert_eq!(KittyModule::total_egg_deposits(), 0);
+		});
+	}
+
+	#[test]
+	fn index_overflow_is_an_error() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_eq!(KittyModule::next_index(u32::max_value() - 1), Ok(u32::max_value()));
+			assert_eq!(KittyModule::next_index(u32::max_value()), Err("Kitty index overflow"));
+
+			<KittiesCount<Test>>::put(u32::max_value());
+			assert_noop!(KittyModule::create(Origin::signed(1)), "Kitty index overflow");
+			assert_eq!(KittyModule::kitties_count(), u32::max_value());
+		});
+	}
+
+	#[test]
+	fn gen0_pairs_breed_free() {
+		with_externalities(&mut new_test_ext_with_kitties(vec![
+			(1, [0x10; 16], 0), (1, [0x21; 16], 0), (1, [0x32; 16], 1), (1, [0x43; 16], 0),
+		]), || {
+			BASE_BREEDING_FEE.with(|v| *v.borrow_mut() = 10);
+			FREE_GEN0_BREED.with(|v| *v.borrow_mut() = true);
+
+			assert_eq!(KittyModule::breeding_fee(0), 0);
+			assert_ok!(KittyModule::breed(Origin::signed(1), 0, 1));
+			assert_eq!(Balances::free_balance(&1), 1000);
+
+			// A generation 1 parent pays the curve fee
+			assert_eq!(KittyModule::breeding_fee(1), 20);
+			assert_ok!(KittyModule::breed(Origin::signed(1), 2, 3));
+			assert_eq!(Balances::free_balance(&1), 980);
+			assert_eq!(Balances::free_balance(&9), 20);
+		});
+	}
+
+	#[test]
+	fn littermates_are_siblings() {
+		with_externalities(&mut new_test_ext_with_kitties(vec![(1, [0x10; 16], 0), (1, [0x21; 16], 0)]), || {
+			GEN0_NO_COOLDOWN.with(|v| *v.borrow_mut() = true);
+			assert_ok!(KittyModule::breed(Origin::signed(1), 0, 1));
+			assert_ok!(KittyModule::breed(Origin::signed(1), 0, 1));
+
+			assert_eq!(KittyModule::children_of(0), vec![2, 3]);
+			assert_eq!(KittyModule::siblings_of(2), vec![3]);
+			assert_eq!(KittyModule::siblings_of(3), vec![2]);
+			assert_eq!(KittyModule::siblings_of(0), Vec::<u32>::new());
+		});
+	}
+
+	#[test]
+	fn sacrifice_raises_level() {
+		with_externalities(&mut new_test_ext_with_kitties(vec![
+			(1, [0x10; 16], 0), (1, [0x21; 16], 0), (1, [0x32; 16], 0), (2, [0x43; 16], 0),
+		]), || {
+			assert_noop!(KittyModule::sacrifice(Origin::signed(1), 0, vec![0]), "Cannot sacrifice the target");
+			assert_noop!(KittyModule::sacrifice(Origin::signed(1), 0, vec![1, 3]), "Not owner of fodder");
+			assert_noop!(KittyModule::sacrifice(Origin::signed(1), 0, vec![1, 1]), "Duplicate kitty in batch");
+
+			assert_ok!(KittyModule::sacrifice(Origin::signed(1), 0, vec![1, 2]));
+
+			assert_eq!(last_event(), TestEvent::kitties(RawEvent::Upgraded(1, 0, 2, 2)));
+			assert_eq!(KittyModule::level_of(0), 2);
+			assert_eq!(KittyModule::kitties(1), None);
+			assert_eq!(KittyModule::kitties(2), None);
+			assert_eq!(KittyModule::owned_kitties_count(&1), 1);
+		});
+	}
+
+	#[test]
+	fn bred_selector_reproduces_child_dna() {
+		with_externalities(&mut new_test_ext_with_kitties(vec![(1, [0x10; 16], 0), (1, [0x2d; 16], 0)]), || {
+			assert_ok!(KittyModule::breed(Origin::signed(1), 0, 1));
+			let (selector, flips) = match last_event() {
+				TestEvent::kitties(RawEvent::Bred(1, 0, 1, 2, selector, flips)) => (selector, flips),
+				event => panic!("unexpected event {:?}", event),
+			};
+
+			let (dna1, dna2) = ([0x10; 16], [0x2d; 16]);
+			let mut dna = [0u8; 16];
+			for i in 0..dna.len() {
+				dna[i] = combine_dna(dna1[i], dna2[i], selector[i]);
+			}
+			dna[0] = (dna[0] & !1) | offspring_gender(&dna1, &dna2, &selector) as u8;
+			assert_eq!(KittyModule::kitties(2), Some(Kitty(dna)));
+			assert_eq!(flips[1..], [0; 15]);
+		});
+	}
+
+	#[test]
+	fn bred_flips_reproduce_adjusted_dna() {
+		let kitties = vec![(1, [0; 16], 0), (1, [2; 16], 0), (1, [4; 16], 0), (1, [0xf0; 16], 0)];
+		with_externalities(&mut new_test_ext_with_kitties(kitties), || {
+			BALANCED_GENDERS.with(|v| *v.borrow_mut() = true);
+			let mut mask = [0; 16];
+			mask[5] = 0xff;
+			LOCKED_TRAIT_MASK.with(|v| *v.borrow_mut() = mask);
+
+			assert_ok!(KittyModule::breed_with_donor(Origin::signed(1), 0, 1, 3));
+			let (selector, flips) = match last_event() {
+				TestEvent::kitties(RawEvent::Bred(1, 0, 1, 4, selector, flips)) => (selector, flips),
+				event => panic!("unexpected event {:?}", event),
+			};
+
+			// The male parents only make a female through the balanced gender
+			assert_eq!(flips[0] & 1, 1);
+			let (dna1, dna2) = ([0; 16], [2; 16]);
+			let mut dna = [0u8; 16];
+			for i in 0..dna.len() {
+				dna[i] = combine_dna(dna1[i], dna2[i], selector[i]) ^ flips[i];
+			}
+			assert_eq!(KittyModule::kitties(4), Some(Kitty(dna)));
+			assert_eq!(dna[12..], [0xf0; 4]);
+		});
+	}
+
+	#[test]
+	fn time_locked_kitty_cannot_move() {
+		with_externalities(&mut new_test_ext_with_kitties(vec![(1, [0x10; 16], 0)]), || {
+			assert_ok!(KittyModule::lock_until(Origin::signed(1), 0, 5));
+			assert_eq!(last_event(), TestEvent::kitties(RawEvent::TimeLocked(0, 5)));
+			assert_noop!(KittyModule::lock_until(Origin::signed(1), 0, 4), "Cannot shorten time lock");
+			assert_noop!(KittyModule::transfer_kitty(Origin::signed(1), 2, 0), "Kitty is time locked");
+
+			system::Module::<Test>::set_block_number(4);
+			assert_noop!(KittyModule::transfer_kitty(Origin::signed(1), 2, 0), "Kitty is time locked");
+
+			system::Module::<Test>::set_block_number(5);
+			assert_ok!(KittyModule::transfer_kitty(Origin::signed(1), 2, 0));
+			assert_eq!(KittyModule::kitty_owner(0), Some(2));
+		});
+	}
+
+	#[test]
+	fn floor_price_per_generation() {
+		with_externalities(&mut new_test_ext_with_kitties(vec![
+			(1, [0x10; 16], 0), (1, [0x21; 16], 1), (2, [0x32; 16], 1), (2, [0x43; 16], 0),
+		]), || {
+			assert_eq!(KittyModule::floor_price(None), None);
+
+			assert_ok!(KittyModule::set_price(Origin::signed(1), 0, Some(80)));
+			assert_ok!(KittyModule::set_price(Origin::signed(1), 1, Some(30)));
+			assert_ok!(KittyModule::set_price(Origin::signed(2), 2, Some(50)));
+
+			assert_eq!(KittyModule::floor_price(None), Some((1, 30)));
+			assert_eq!(KittyModule::floor_price(Some(0)), Some((0, 80)));
+			assert_eq!(KittyModule::floor_price(Some(1)), Some((1, 30)));
+			assert_eq!(KittyModule::floor_price(Some(2)), None);
+		});
+	}
+
+	#[test]
+	fn owned_with_prices_shows_listings() {
+		with_externalities(&mut new_test_ext_with_kitties(vec![
+			(1, [0x10; 16], 0), (1, [0x21; 16], 0), (2, [0x32; 16], 0), (1, [0x43; 16], 0),
+		]), || {
+			assert_ok!(KittyModule::set_price(Origin::signed(1), 1, Some(40)));
+
+			assert_eq!(KittyModule::owned_with_prices(&1), vec![(0, None), (1, Some(40)), (3, None)]);
+			assert_eq!(KittyModule::owned_with_prices(&3), vec![]);
+		});
+	}
+
+	#[test]
+	fn pairings_bred_in_one_block_get_different_selectors() {
+		with_externalities(&mut new_test_ext_with_kitties(vec![(1, [0x10; 16], 0), (1, [0x21; 16], 0), (1, [0x32; 16], 0)]), || {
+			GEN0_NO_COOLDOWN.with(|v| *v.borrow_mut() = true);
+			let mut selectors = Vec::new();
+			for &sire in [1, 2].iter() {
+				assert_ok!(KittyModule::breed(Origin::signed(1), 0, sire));
+				match last_event() {
+					TestEvent::kitties(RawEvent::Bred(_, _, _, _, selector, _)) => selectors.push(selector),
+					event => panic!("unexpected event {:?}", event),
+				}
+			}
+			assert_ne!(selectors[0], selectors[1]);
+		});
+	}
+
+	#[test]
+	fn pair_offspring_is_capped() {
+		with_externalities(&mut new_test_ext_with_kitties(vec![(1, [0x10; 16], 0), (1, [0x21; 16], 0), (1, [0x32; 16], 0)]), || {
+			GEN0_NO_COOLDOWN.with(|v| *v.borrow_mut() = true);
+			MAX_PAIR_OFFSPRING.with(|v| *v.borrow_mut() = 2);
+
+			assert_ok!(KittyModule::breed(Origin::signed(1), 0, 1));
+			assert_ok!(KittyModule::breed(Origin::signed(1), 1, 0));
+			assert_eq!(KittyModule::pair_offspring_count(&0, &1), 2);
+			assert_noop!(KittyModule::breed(Origin::signed(1), 0, 1), "Pair offspring limit reached");
+			assert_noop!(KittyModule::breed(Origin::signed(1), 1, 0), "Pair offspring limit reached");
+			assert_eq!(KittyModule::breeding_compatibility(0, 1).reason, Some(Incompatibility::PairLimit));
+
+			// Each parent can still breed with another partner
+			assert_ok!(KittyModule::breed(Origin::signed(1), 0, 2));
+			assert_ok!(KittyModule::breed(Origin::signed(1), 2, 1));
+		});
+	}
+
+	#[test]
+	fn gender_distribution_follows_births_and_burns() {
+		with_externalities(&mut new_test_ext_with_kitties(vec![(1, [0x10; 16], 0), (1, [0x21; 16], 0), (1, [0x32; 16], 0)]), || {
+			assert_eq!(KittyModule::gender_distribution(), (2, 1));
+
+			assert_ok!(KittyModule::breed(Origin::signed(1), 0, 1));
+			let expected = match KittyModule::kitties(3).unwrap().gender() {
+				Gender::Male => (3, 1),
+				Gender::Female => (2, 2),
+			};
+			assert_eq!(KittyModule::gender_distribution(), expected);
+
+			assert_ok!(KittyModule::burn(Origin::signed(1), 2));
+			assert_eq!(KittyModule::gender_distribution(), (expected.0 - 1, expected.1));
+		});
+	}
+
+	#[test]
+	fn appraisal_is_pinned() {
+		let mut dna = [0x10; 16];
+		dna[1] = 0xf0;
+		dna[2] = 0xff;
+		with_externalities(&mut new_test_ext_with_kitties(vec![(1, dna, 1), (1, [0x21; 16], 1)]), || {
+			assert_eq!(KittyModule::appraise(0), Some(1500));
+
+			assert_ok!(KittyModule::breed(Origin::signed(1), 0, 1));
+			assert_eq!(KittyModule::appraise(0), Some(750));
+			assert_ok!(KittyModule::retire(Origin::signed(1), 0));
+			assert_eq!(KittyModule::appraise(0), Some(375));
+			assert_eq!(KittyModule::appraise(9), None);
+		});
+		assert_eq!(appraisal(0, 0, 0, false), 1000);
+		assert_eq!(appraisal(16, 10, 3, false), 386);
+	}
+
+	#[test]
+	fn transfers_emit_events() {
+		with_externalities(&mut new_test_ext_with_kitties(vec![(1, [0x10; 16], 0), (1, [0x21; 16], 0), (1, [0x32; 16], 0)]), || {
+			assert_ok!(KittyModule::transfer_kitty(Origin::signed(1), 2, 0));
+			assert_eq!(last_event(), TestEvent::kitties(RawEvent::Transferred(1, 2, 0)));
+
+			assert_ok!(KittyModule::batch_transfer(Origin::signed(1), 3, vec![1, 2]));
+			let transferred: Vec<_> = system::Module::<Test>::events().into_iter()
+				.filter_map(|record| match record.event {
+					TestEvent::kitties(RawEvent::Transferred(from, to, kitty_id)) => Some((from, to, kitty_id)),
+					_ => None,
+				})
+				.collect();
+			assert_eq!(transferred, vec![(1, 2, 0), (1, 3, 1), (1, 3, 2)]);
+		});
+	}
+
+	#[test]
+	fn buy_pays_seller() {
+		with_externalities(&mut new_test_ext_with_kitties(vec![(1, [0x10; 16], 0)]), || {
+			assert_noop!(KittyModule::buy(Origin::signed(2), 0, 100), "Kitty is not for sale");
+			assert_ok!(KittyModule::set_price(Origin::signed(1), 0, Some(100)));
+
+			assert_noop!(KittyModule::buy(Origin::signed(2), 0, 99), "Price above max price");
+			assert_noop!(KittyModule::buy(Origin::signed(1), 0, 100), "Cannot buy own kitty");
+			assert_noop!(KittyModule::buy(Origin::signed(4), 0, 100), "Cannot pay kitty price");
+
+			assert_ok!(KittyModule::buy(Origin::signed(2), 0, 150));
+
+			assert_eq!(last_event(), TestEvent::kitties(RawEvent::Sold(2, 1, 0, 100)));
+			assert_eq!(KittyModule::kitty_owner(0), Some(2));
+			assert_eq!(KittyModule::kitty_price(0), None);
+			assert_eq!(Balances::free_balance(&1), 1100);
+			assert_eq!(Balances::free_balance(&2), 900);
+		});
+	}
+
+	#[test]
+	fn owned_list_unlinks_and_appends() {
+		let kitties = vec![(1, [0x10; 16], 0), (1, [0x21; 16], 0), (1, [0x32; 16], 0), (1, [0x43; 16], 0)];
+		with_externalities(&mut new_test_ext_with_kitties(kitties), || {
+			assert_ok!(KittyModule::transfer_kitty(Origin::signed(1), 2, 1));
+			assert_ok!(KittyModule::transfer_kitty(Origin::signed(1), 2, 3));
+			assert_eq!(KittyModule::kitties_of(&1), vec![0, 2]);
+			assert_eq!(KittyModule::owned_kitties((1, Some(2))), Some(KittyLinkedItem { prev: Some(0), next: None }));
+
+			assert_ok!(KittyModule::transfer_kitty(Origin::signed(2), 1, 1));
+			assert_eq!(KittyModule::kitties_of(&1), vec![0, 2, 1]);
+			assert_eq!(KittyModule::kitties_of(&2), vec![3]);
+
+			for kitty_id in [0, 2, 1].iter() {
+				assert_ok!(KittyModule::burn(Origin::signed(1), *kitty_id));
+			}
+			assert_eq!(KittyModule::kitties_of(&1), vec![]);
+			assert_eq!(KittyModule::owned_kitties((1, None)), Some(KittyLinkedItem::default()));
+			assert_ok!(KittyModule::try_state());
+		});
+	}
+
+	#[test]
+	fn root_sets_breed_fee() {
+		with_externalities(&mut new_test_ext_with_kitties(vec![
+			(1, [0x10; 16], 0), (1, [0x21; 16], 0), (1, [0x32; 16], 0), (1, [0x43; 16], 0),
+		]), || {
+			assert_noop!(KittyModule::set_breed_fee(Origin::signed(1), Some(30)), "bad origin: expected to be a root origin");
+			assert_ok!(KittyModule::set_breed_fee(Origin::ROOT, Some(30)));
+			assert_eq!(last_event(), TestEvent::kitties(RawEvent::BreedFeeSet(Some(30))));
+			assert_eq!(KittyModule::breeding_fee(1), 60);
+
+			assert_ok!(KittyModule::breed(Origin::signed(1), 0, 1));
+			assert_eq!(Balances::free_balance(&1), 970);
+			assert_eq!(Balances::free_balance(&9), 30);
+
+			// Without an override the configured base fee applies again
+			assert_ok!(KittyModule::set_breed_fee(Origin::ROOT, None));
+			assert_eq!(KittyModule::breed_fee(), None);
+			assert_ok!(KittyModule::breed(Origin::signed(1), 2, 3));
+			assert_eq!(Balances::free_balance(&1), 970);
+		});
+	}
+
+	#[test]
+	fn fees_need_an_existing_treasury() {
+		EXISTENTIAL_DEPOSIT.with(|v| *v.borrow_mut() = 50);
+		with_externalities(&mut new_test_ext_with_kitties(vec![
+			(1, [0x10; 16], 0), (1, [0x21; 16], 0), (1, [0x32; 16], 0), (1, [0x43; 16], 0),
+		]), || {
+			BASE_BREEDING_FEE.with(|v| *v.borrow_mut() = 10);
+
+			// A fee below the existential deposit can't create the treasury account
+			assert_eq!(KittyModule::breed(Origin::signed(1), 0, 1), Err("Cannot pay breeding fee"));
+			assert_eq!(KittyModule::kitties_count(), 4);
+
+			Balances::make_free_balance_be(&9, 50);
+			assert_ok!(KittyModule::breed(Origin::signed(1), 2, 3));
+			assert_eq!(Balances::free_balance(&9), 60);
+		});
+	}
+
+	#[test]
+	fn failed_breed_charges_no_fee() {
+		with_externalities(&mut new_test_ext_with_kitties(vec![(1, [0x10; 16], 0), (1, [0x21; 16], 0)]), || {
+			BASE_BREEDING_FEE.with(|v| *v.borrow_mut() = 10);
+			MAX_KITTIES_PER_ACCOUNT.with(|v| *v.borrow_mut() = 2);
+
+			assert_eq!(KittyModule::breed(Origin::signed(1), 0, 1), Err("Too many kitties owned"));
+			assert_eq!(Balances::free_balance(&1), 1000);
+			assert_eq!(Balances::free_balance(&9), 0);
+			assert_eq!(KittyModule::kitties_count(), 2);
+		});
+	}
+
+	#[test]
+	fn buys_count_against_seller_rate() {
+		let kitties = vec![(1, [0x10; 16], 0), (1, [0x20; 16], 0), (1, [0x30; 16], 0)];
+		with_externalities(&mut new_test_ext_with_kitties(kitties), || {
+			MAX_TRANSFERS_PER_WINDOW.with(|v| *v.borrow_mut() = 2);
+			system::Module::<Test>::set_block_number(1);
+			for kitty_id in 0..3 {
+				assert_ok!(KittyModule::set_price(Origin::signed(1), kitty_id, Some(0)));
+			}
+
+			assert_ok!(KittyModule::buy(Origin::signed(2), 0, 0));
+			assert_ok!(KittyModule::buy(Origin::signed(2), 1, 0));
+			assert_noop!(KittyModule::buy(Origin::signed(2), 2, 0), "Transfer rate limited");
+			assert_eq!(KittyModule::kitty_owner(2), Some(1));
+		});
+	}
+
+	#[test]
+	fn auction_settlement_counts_against_seller_rate() {
+		with_externalities(&mut new_test_ext_with_kitties(vec![(1, [0x10; 16], 0), (1, [0x20; 16], 0)]), || {
+			MAX_TRANSFERS_PER_WINDOW.with(|v| *v.borrow_mut() = 1);
+			system::Module::<Test>::set_block_number(1);
+			assert_ok!(KittyModule::create_auction(Origin::signed(1), 0, 10, 5));
+			assert_ok!(KittyModule::bid(Origin::signed(2), 0, 20));
+			assert_ok!(KittyModule::transfer_kitty(Origin::signed(1), 3, 1));
+
+			system::Module::<Test>::set_block_number(6);
+			assert_noop!(KittyModule::close_auction(Origin::signed(2), 0), "Transfer rate limited");
+			assert_eq!(KittyModule::kitty_owner(0), Some(1));
+			assert!(KittyModule::auction_of(0).is_some());
+
+			// The next window has room for the settlement
+			system::Module::<Test>::set_block_number(11);
+			assert_ok!(KittyModule::close_auction(Origin::signed(2), 0));
+			assert_eq!(KittyModule::kitty_owner(0), Some(2));
+			assert_eq!(KittyModule::transfer_count(&1), (11, 1));
+		});
+	}
+
+	#[test]
+	fn auction_close_leaves_state_intact_on_failure() {
+		with_externalities(&mut new_test_ext_with_kitties(vec![(5, [0; 16], 0)]), || {
+			system::Module::<Test>::set_block_number(1);
+			assert_ok!(KittyModule::create_auction(Origin::signed(5), 0, 10, 5));
+			assert_ok!(KittyModule::bid(Origin::signed(2), 0, 20));
+
+			system::Module::<Test>::set_block_number(6);
+			// The seller has no balance to be paid into
+			assert_noop!(KittyModule::close_auction(Origin::signed(2), 0), "Seller account doesn't exist");
+			assert_eq!(KittyModule::kitty_owner(0), Some(5));
+			assert!(KittyModule::auction_of(0).is_some());
+			assert!(KittyModule::is_locked(0));
+			assert_eq!(Balances::reserved_balance(&2), 20);
+
+			Balances::make_free_balance_be(&5, 1);
+			assert_ok!(KittyModule::close_auction(Origin::signed(2), 0));
+			assert_eq!(KittyModule::kitty_owner(0), Some(2));
+			assert_eq!(KittyModule::auction_of(0), None);
+			assert_eq!(Balances::free_balance(&5), 21);
+			assert_eq!(Balances::reserved_balance(&2), 0);
+		});
+	}
+}
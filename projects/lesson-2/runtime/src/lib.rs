@@ -33,7 +33,7 @@ pub use sr_primitives::BuildStorage;
 pub use timestamp::Call as TimestampCall;
 pub use balances::Call as BalancesCall;
 pub use sr_primitives::{Permill, Perbill};
-pub use support::{StorageValue, construct_runtime, parameter_types};
+pub use support::{StorageValue, construct_runtime, parameter_types, traits::Get};
 
 /// An index to a block.
 pub type BlockNumber = u32;
@@ -61,9 +61,14 @@ pub type Hash = primitives::H256;
 /// Digest item type.
 pub type DigestItem = generic::DigestItem<Hash>;
 
+/// Index of a kitty.
+pub type KittyIndex = u32;
+
 /// Used for the module template in `./template.rs`
 mod template;
 
+pub mod kitties;
+
 /// Opaque types. These are used by the CLI to instantiate machinery that don't need to know
 /// the specifics of the runtime. They can then be made to be agnostic over specific formats
 /// of data like extrinsics, allowing for them to continue syncing the network through upgrades
@@ -256,6 +261,133 @@ impl template::Trait for Runtime {
 	type Event = Event;
 }
 
+parameter_types! {
+	pub const BreedCooldown: BlockNumber = 10 * MINUTES;
+	pub const Gen0NoCooldown: bool = false;
+	pub const DeterministicGenetics: bool = false;
+	pub const MaxGenerationGap: u16 = u16::max_value();
+	pub const MaxGeneration: u16 = 100;
+	pub const MinCreatorBalance: Balance = 1_000;
+	pub const DonorGeneCount: u32 = 4;
+	pub const RarityBiasedBreeding: bool = false;
+	pub const MaxKittiesPerAccount: KittyIndex = 1_000;
+	pub const RankingInterval: BlockNumber = 1 * HOURS;
+	pub const BurnReward: Balance = 10;
+	pub const StarterPackSize: u32 = 3;
+	pub const KittyTransferFee: Balance = 0;
+	pub const BreedingTokenReward: u32 = 1;
+	pub const BaseBreedingFee: Balance = 100;
+	pub const AntiSnipeWindow: BlockNumber = 10;
+	pub const MaxTransfersPerWindow: u32 = 100;
+	pub const TransferWindow: BlockNumber = 1 * HOURS;
+	pub const MaxBatchSize: u32 = 20;
+	pub const ResetCooldownOnTransfer: bool = false;
+	pub const MinBidIncrement: Balance = 10;
+	pub const UniqueNames: bool = true;
+	pub const BalancedGenders: bool = true;
+	pub const Incubation: bool = false;
+	pub const HatchFee: Balance = 100;
+	pub const LockedTraitMask: [u8; 16] = [0; 16];
+	pub const MaxListingPrice: Balance = Balance::max_value();
+	pub const CreationDeposit: Balance = 100;
+	pub const DepositFollowsKitty: bool = false;
+	pub const SpeedupFee: Balance = 100;
+	pub const PresaleEnd: BlockNumber = 0;
+	pub const SameSpeciesOnly: bool = false;
+	pub const CompactBatchEvents: bool = true;
+	pub const UseTombstones: bool = false;
+	pub const MaxLicenseTier: u8 = 3;
+	pub const LicenseUpgradeFee: Balance = 100;
+	pub const MaxOperatorsPerOwner: u32 = 16;
+	pub const EggDeposit: Balance = 100;
+	pub const EggRevealDelay: BlockNumber = 2;
+	pub const EggRevealWindow: BlockNumber = 1 * HOURS;
+	pub const FreeGen0Breed: bool = false;
+	pub const LevelsPerFodder: u32 = 1;
+	pub const MaxPairOffspring: u32 = 10;
+}
+
+/// Breeding fee multiplier growing by one every 10 generations.
+pub struct BreedingFeeCurve;
+impl sr_primitives::traits::Convert<u16, u32> for BreedingFeeCurve {
+	fn convert(generation: u16) -> u32 {
+		1 + generation as u32 / 10
+	}
+}
+
+/// Account paying the kitties module rewards, it has no known private key.
+///
+/// It is endowed at genesis, fees below the existential deposit can't create it.
+pub struct KittiesTreasury;
+impl support::traits::Get<AccountId> for KittiesTreasury {
+	fn get() -> AccountId {
+		AccountId::default()
+	}
+}
+
+/// Account signing kitty minting vouchers, none can be redeemed until a key is set here.
+pub struct KittiesVoucherSigner;
+impl support::traits::Get<AccountId> for KittiesVoucherSigner {
+	fn get() -> AccountId {
+		AccountId::default()
+	}
+}
+
+impl kitties::Trait for Runtime {
+	type Event = Event;
+	type Call = Call;
+	type UncheckedExtrinsic = UncheckedExtrinsic;
+	type KittyIndex = KittyIndex;
+	type Currency = Balances;
+	type BreedCooldown = BreedCooldown;
+	type Gen0NoCooldown = Gen0NoCooldown;
+	type DeterministicGenetics = DeterministicGenetics;
+	type MaxGenerationGap = MaxGenerationGap;
+	type MaxGeneration = MaxGeneration;
+	type MinCreatorBalance = MinCreatorBalance;
+	type DonorGeneCount = DonorGeneCount;
+	type RarityBiasedBreeding = RarityBiasedBreeding;
+	type MaxKittiesPerAccount = MaxKittiesPerAccount;
+	type RankingInterval = RankingInterval;
+	type Treasury = KittiesTreasury;
+	type BurnReward = BurnReward;
+	type StarterPackSize = StarterPackSize;
+	type TransferFee = KittyTransferFee;
+	type BreedingTokenReward = BreedingTokenReward;
+	type BaseBreedingFee = BaseBreedingFee;
+	type BreedingFeeCurve = BreedingFeeCurve;
+	type AntiSnipeWindow = AntiSnipeWindow;
+	type MaxTransfersPerWindow = MaxTransfersPerWindow;
+	type TransferWindow = TransferWindow;
+	type MaxBatchSize = MaxBatchSize;
+	type ResetCooldownOnTransfer = ResetCooldownOnTransfer;
+	type MinBidIncrement = MinBidIncrement;
+	type UniqueNames = UniqueNames;
+	type Signature = Signature;
+	type VoucherSigner = KittiesVoucherSigner;
+	type BalancedGenders = BalancedGenders;
+	type Incubation = Incubation;
+	type HatchFee = HatchFee;
+	type LockedTraitMask = LockedTraitMask;
+	type MaxListingPrice = MaxListingPrice;
+	type CreationDeposit = CreationDeposit;
+	type DepositFollowsKitty = DepositFollowsKitty;
+	type SpeedupFee = SpeedupFee;
+	type PresaleEnd = PresaleEnd;
+	type SameSpeciesOnly = SameSpeciesOnly;
+	type CompactBatchEvents = CompactBatchEvents;
+	type UseTombstones = UseTombstones;
+	type MaxLicenseTier = MaxLicenseTier;
+	type LicenseUpgradeFee = LicenseUpgradeFee;
+	type MaxOperatorsPerOwner = MaxOperatorsPerOwner;
+	type EggDeposit = EggDeposit;
+	type EggRevealDelay = EggRevealDelay;
+	type EggRevealWindow = EggRevealWindow;
+	type FreeGen0Breed = FreeGen0Breed;
+	type LevelsPerFodder = LevelsPerFodder;
+	type MaxPairOffspring = MaxPairOffspring;
+}
+
 construct_runtime!(
 	pub enum Runtime where
 		Block = Block,
@@ -271,6 +403,7 @@ construct_runtime!(
 		Sudo: sudo,
 		// Used for the module template in `./template.rs`
 		TemplateModule: template::{Module, Call, Storage, Event<T>},
+		Kitties: kitties::{Module, Call, Storage, Event<T>, Config<T>, ValidateUnsigned},
 	}
 );
 
@@ -399,6 +532,88 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl kitties::KittiesApi<Block, AccountId, KittyIndex, Balance, BlockNumber> for Runtime {
+		fn kitties_by_ids(ids: Vec<KittyIndex>) -> Vec<Option<kitties::Kitty>> {
+			Kitties::kitties_by_ids(ids)
+		}
+
+		fn account_summary(account: AccountId) -> kitties::AccountSummary<KittyIndex, Balance> {
+			Kitties::account_summary(&account)
+		}
+
+		fn kitties_age(kitty_id: KittyIndex) -> Option<BlockNumber> {
+			Kitties::kitty_age(kitty_id)
+		}
+
+		fn kitties_id_by_dna(dna: [u8; 16]) -> Option<KittyIndex> {
+			Kitties::kitties_id_by_dna(dna)
+		}
+
+		fn owners_of(ids: Vec<KittyIndex>) -> Vec<Option<AccountId>> {
+			Kitties::owners_of(ids)
+		}
+
+		fn kitties_snapshot(start: KittyIndex, limit: u32) -> Vec<(KittyIndex, kitties::Kitty, AccountId)> {
+			Kitties::kitties_snapshot(start, limit)
+		}
+
+		fn kitty_index_info() -> kitties::KittyIndexInfo<KittyIndex> {
+			Kitties::kitty_index_info()
+		}
+
+		fn kitties_render_traits(kitty_id: KittyIndex) -> Option<Vec<u8>> {
+			Kitties::kitties_render_traits(kitty_id)
+		}
+
+		fn total_reserved() -> Balance {
+			Kitties::total_reserved()
+		}
+
+		fn active_listings(start: KittyIndex, limit: u32) -> Vec<(KittyIndex, AccountId, Balance)> {
+			Kitties::active_listings(start, limit)
+		}
+
+		fn breeding_compatibility(kitty_id_1: KittyIndex, kitty_id_2: KittyIndex) -> kitties::CompatibilityReport<BlockNumber> {
+			Kitties::breeding_compatibility(kitty_id_1, kitty_id_2)
+		}
+
+		fn ready_to_breed(account: AccountId) -> Vec<KittyIndex> {
+			Kitties::ready_to_breed(&account)
+		}
+
+		fn kitty_profile(kitty_id: KittyIndex) -> Option<kitties::KittyProfile<AccountId, Balance>> {
+			Kitties::kitty_profile(kitty_id)
+		}
+
+		fn birth_certificate(kitty_id: KittyIndex) -> Option<kitties::BirthCertificate<AccountId, KittyIndex, BlockNumber>> {
+			Kitties::birth_certificate(kitty_id)
+		}
+
+		fn dry_run_create(account: AccountId) -> Result<KittyIndex, Vec<u8>> {
+			Kitties::dry_run_create(&account).map_err(|error| error.as_bytes().to_vec())
+		}
+
+		fn siblings_of(kitty_id: KittyIndex) -> Vec<KittyIndex> {
+			Kitties::siblings_of(kitty_id)
+		}
+
+		fn floor_price(generation: Option<u16>) -> Option<(KittyIndex, Balance)> {
+			Kitties::floor_price(generation)
+		}
+
+		fn owned_with_prices(account: AccountId) -> Vec<(KittyIndex, Option<Balance>)> {
+			Kitties::owned_with_prices(&account)
+		}
+
+		fn gender_distribution() -> (u32, u32) {
+			Kitties::gender_distribution()
+		}
+
+		fn appraise(kitty_id: KittyIndex) -> Option<u64> {
+			Kitties::appraise(kitty_id)
+		}
+	}
+
 	impl substrate_session::SessionKeys<Block> for Runtime {
 		fn generate_session_keys(seed: Option<Vec<u8>>) -> Vec<u8> {
 			let seed = seed.as_ref().map(|s| rstd::str::from_utf8(&s).expect("Seed is an utf8 string"));
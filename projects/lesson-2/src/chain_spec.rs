@@ -1,7 +1,7 @@
 use primitives::{Pair, Public};
 use substrate_kitties_runtime::{
 	AccountId, BabeConfig, BalancesConfig, GenesisConfig, GrandpaConfig,
-	SudoConfig, IndicesConfig, SystemConfig, WASM_BINARY, 
+	SudoConfig, IndicesConfig, SystemConfig, KittiesConfig, KittiesTreasury, Get, WASM_BINARY,
 };
 use babe_primitives::{AuthorityId as BabeId};
 use grandpa_primitives::{AuthorityId as GrandpaId};
@@ -119,7 +119,10 @@ fn testnet_genesis(initial_authorities: Vec<(AccountId, AccountId, GrandpaId, Ba
 			ids: endowed_accounts.clone(),
 		}),
 		balances: Some(BalancesConfig {
-			balances: endowed_accounts.iter().cloned().map(|k|(k, 1 << 60)).collect(),
+			balances: endowed_accounts.iter().cloned().map(|k|(k, 1 << 60))
+				// The treasury receives the kitty fees and pays the burn rewards
+				.chain(std::iter::once((KittiesTreasury::get(), 1 << 50)))
+				.collect(),
 			vesting: vec![],
 		}),
 		sudo: Some(SudoConfig {
@@ -131,5 +134,8 @@ fn testnet_genesis(initial_authorities: Vec<(AccountId, AccountId, GrandpaId, Ba
 		grandpa: Some(GrandpaConfig {
 			authorities: initial_authorities.iter().map(|x| (x.2.clone(), 1)).collect(),
 		}),
+		kitties: Some(KittiesConfig {
+			kitties: vec![],
+		}),
 	}
 }